@@ -0,0 +1,31 @@
+//! Measures how long it takes to cancel timers as the number of pending timers grows. The
+//! time per cancellation should stay roughly flat.
+//!
+//! ```text
+//! cargo bench --bench cancel
+//! ```
+
+use bevy_tick_timers::Timers;
+use std::time::Instant;
+
+const CANCELLED: usize = 1_000;
+
+fn main() {
+    for pending in [1_000, 10_000, 100_000] {
+        let mut timers = Timers::default();
+        timers.set_leak_threshold(None);
+        let handles: Vec<_> = (0..pending)
+            .map(|i| timers.after(i * 7 % 200_000, |_| {}))
+            .collect();
+
+        let start = Instant::now();
+        for handle in handles.iter().step_by(pending / CANCELLED) {
+            assert!(timers.cancel(*handle));
+        }
+        println!(
+            "{} pending timers: {:?} per cancellation",
+            pending,
+            start.elapsed() / CANCELLED as u32
+        );
+    }
+}
@@ -0,0 +1,52 @@
+//! Fires many read-only timers on the same tick and reports the time taken per tick. Compare
+//! the results with and without the `rayon` feature:
+//!
+//! ```text
+//! cargo bench --bench read_only
+//! cargo bench --bench read_only --features rayon
+//! ```
+
+use bevy::prelude::*;
+use bevy_tick_timers::{TimerPlugin, Timers};
+use std::time::{Duration, Instant};
+
+const TIMERS: usize = 1_000;
+const ROUNDS: u32 = 20;
+
+/// The data read by every timer.
+struct Samples(Vec<u64>);
+
+/// The sum computed by the last timer to apply its commands.
+#[derive(Default)]
+struct Total(u64);
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugin(TimerPlugin)
+        .insert_resource(Samples((0..10_000).collect()))
+        .init_resource::<Total>();
+
+    let mut elapsed = Duration::ZERO;
+    for _ in 0..ROUNDS {
+        #[cfg(not(feature = "single_thread"))]
+        let mut timers = app.world.get_resource_mut::<Timers>().unwrap();
+        #[cfg(feature = "single_thread")]
+        let mut timers = app.world.get_non_send_resource_mut::<Timers>().unwrap();
+        for i in 0..TIMERS as u64 {
+            timers.after_read(0, move |world, commands| {
+                let samples = &world.get_resource::<Samples>().unwrap().0;
+                let sum = samples.iter().map(|sample| sample ^ i).sum();
+                commands.insert_resource(Total(sum));
+            });
+        }
+        let start = Instant::now();
+        app.update();
+        elapsed += start.elapsed();
+    }
+    println!(
+        "{} read-only timers: {:?} per tick (checksum {})",
+        TIMERS,
+        elapsed / ROUNDS,
+        app.world.get_resource::<Total>().unwrap().0
+    );
+}
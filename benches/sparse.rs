@@ -0,0 +1,77 @@
+//! Schedules many timers spread thinly across the wheel and reports the time taken and the
+//! number of heap allocations made. Compare the results with and without the `smallvec`
+//! feature:
+//!
+//! ```text
+//! cargo bench --bench sparse
+//! cargo bench --bench sparse --features smallvec
+//! ```
+
+use bevy::prelude::*;
+use bevy_tick_timers::{TimerPlugin, Timers};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const TIMERS: usize = 10_000;
+const SPACING: usize = 7;
+
+/// Run the app for enough ticks to fire every timer, returning the number of allocations
+/// made, the time taken to schedule the timers and the total time taken.
+fn run(timers: usize) -> (usize, Duration, Duration) {
+    let mut app = App::new();
+    app.add_plugin(TimerPlugin);
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    #[cfg(not(feature = "single_thread"))]
+    let mut resource = app.world.get_resource_mut::<Timers>().unwrap();
+    #[cfg(feature = "single_thread")]
+    let mut resource = app.world.get_non_send_resource_mut::<Timers>().unwrap();
+    for i in 0..timers {
+        resource.after(i * SPACING, |_| {});
+    }
+    let scheduled = start.elapsed();
+    for _ in 0..TIMERS * SPACING {
+        app.update();
+    }
+    let total = start.elapsed();
+    (
+        ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        scheduled,
+        total,
+    )
+}
+
+fn main() {
+    // Running the app allocates on its own, so subtract a run without any timers.
+    let (baseline, _, _) = run(0);
+    let (allocations, scheduled, total) = run(TIMERS);
+    println!(
+        "{} sparse timers: scheduled in {:?}, all fired after {:?}, {} allocations",
+        TIMERS,
+        scheduled,
+        total,
+        allocations - baseline
+    );
+}
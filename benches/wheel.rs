@@ -0,0 +1,89 @@
+//! Measures the cost of the basic wheel operations: scheduling timers, ticking an empty
+//! wheel, draining a burst of timers that fire on the same tick, and cascading timers down
+//! at level boundaries. The work counters are printed alongside the timings, so that a
+//! change in timing can be told apart from a change in the amount of work done.
+//!
+//! ```text
+//! cargo bench --bench wheel
+//! ```
+
+use bevy::prelude::*;
+use bevy_tick_timers::Timers;
+use std::time::{Duration, Instant};
+
+const TIMERS: usize = 100_000;
+const TICKS: usize = 100_000;
+
+/// Return a world holding an empty [Timers] resource.
+fn world() -> World {
+    let mut world = World::new();
+    let mut timers = Timers::default();
+    timers.set_leak_threshold(None);
+    #[cfg(not(feature = "single_thread"))]
+    world.insert_resource(timers);
+    #[cfg(feature = "single_thread")]
+    world.insert_non_send(timers);
+    world
+}
+
+#[cfg(not(feature = "single_thread"))]
+fn timers(world: &mut World) -> Mut<'_, Timers> {
+    world.get_resource_mut::<Timers>().unwrap()
+}
+
+#[cfg(feature = "single_thread")]
+fn timers(world: &mut World) -> Mut<'_, Timers> {
+    world.get_non_send_resource_mut::<Timers>().unwrap()
+}
+
+/// Advance the clock by the given number of ticks, running every timer that fires.
+fn tick(world: &mut World, ticks: usize) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ticks {
+        Timers::drain_budgeted(world, None);
+    }
+    start.elapsed()
+}
+
+fn report(name: &str, elapsed: Duration, per: usize, world: &mut World) {
+    let timers = timers(world);
+    println!(
+        "{}: {:?} each ({} fires, {} cascades, {} migrations)",
+        name,
+        elapsed / per as u32,
+        timers.fires(),
+        timers.cascades(),
+        timers.migrations()
+    );
+}
+
+fn main() {
+    let mut world = world();
+    let start = Instant::now();
+    for i in 0..TIMERS {
+        timers(&mut world).after(i * 7 % 200_000, |_| {});
+    }
+    report("schedule", start.elapsed(), TIMERS, &mut world);
+
+    let mut world = self::world();
+    let elapsed = tick(&mut world, TICKS);
+    report("empty tick", elapsed, TICKS, &mut world);
+
+    let mut world = self::world();
+    for _ in 0..TIMERS {
+        timers(&mut world).after(0, |_| {});
+    }
+    let elapsed = tick(&mut world, 1);
+    report("burst drain", elapsed, TIMERS, &mut world);
+
+    // Every timer sits on the second level until the first level wraps around, and is then
+    // cascaded down on the same tick.
+    let mut world = self::world();
+    tick(&mut world, 1);
+    for i in 0..TIMERS {
+        timers(&mut world).after(63 + i % 64, |_| {});
+    }
+    tick(&mut world, 63);
+    let elapsed = tick(&mut world, 1);
+    report("cascade", elapsed, TIMERS, &mut world);
+}
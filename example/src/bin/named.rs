@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+use bevy_tick_timers::{HandlerRegistry, TimerParams, TimerPlugin, Timers};
+use std::collections::BTreeMap;
+
+// Timed events authored as data: (delay, handler, params).
+const TIMERS: &str = include_str!("../../timers.ron");
+
+fn say(_world: &mut World, params: &TimerParams) {
+    let message: String = params.get("message").unwrap_or_default();
+    println!("{}", message);
+}
+
+fn spawn(_world: &mut World, params: &TimerParams) {
+    let kind: String = params.get("kind").unwrap_or_default();
+    let count: usize = params.get("count").unwrap_or(1);
+    println!("spawning {} {}", count, kind);
+}
+
+fn register_handlers(mut registry: ResMut<HandlerRegistry>) {
+    registry.register_or_panic("say", say);
+    registry.register_or_panic("spawn", spawn);
+}
+
+fn load_timers(mut timers: ResMut<Timers>, registry: Res<HandlerRegistry>) {
+    let entries: Vec<(usize, String, BTreeMap<String, String>)> =
+        ron::from_str(TIMERS).expect("invalid timers.ron");
+    for (after, handler, params) in entries {
+        timers.after_named(&registry, after, &handler, TimerParams(params));
+    }
+}
+
+fn main() {
+    App::new()
+        .add_plugins(MinimalPlugins)
+        .add_plugin(TimerPlugin)
+        .add_startup_system_to_stage(StartupStage::PreStartup, register_handlers.system())
+        .add_startup_system(load_timers.system())
+        .run();
+}
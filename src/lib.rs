@@ -12,7 +12,11 @@
 //!use bevy_tick_timers::{TimerPlugin, Timers};
 //!
 //!fn add_timer(
-//!    mut timers: ResMut<Timers>,
+#![cfg_attr(
+    not(feature = "single_thread"),
+    doc = "    mut timers: ResMut<Timers>,"
+)]
+#![cfg_attr(feature = "single_thread", doc = "    mut timers: NonSendMut<Timers>,")]
 //!) {
 //!    // Timers are closures that take the world as a mutable reference.
 //!    timers.after(5, |_| {
@@ -22,144 +26,6276 @@
 //!
 //!fn main() {
 //!    println!("starting up");
-//!    App::build()
+//!    App::new()
 //!        .add_plugins(DefaultPlugins)
 //!        .add_plugin(TimerPlugin)
 //!        .add_startup_system(add_timer.system())
 //!        .run();
 //!}
 //!```
+//!
+//! Timers are run in their own [TimerStage], which is added after all of the other stages when
+//! the plugin is built. Use the label to order your own stages relative to it, or use
+//! [TimerPlugin::run_in] to place the timer stage directly after another stage instead, or
+//! [TimerPlugin::exclusive_system_in] to run the timers as an exclusive system in an existing
+//! stage.
+//! Systems that must run right before or after the timers can be added to the
+//! [TimerDrainStage] stages on either side of it.
+//!
+//! With the `single_thread` feature enabled, timer closures no longer need to be `Send + Sync`,
+//! and [Timers] is stored as a non-send resource, accessed with `NonSendMut<Timers>` rather
+//! than `ResMut<Timers>`.
+//!
+//! With the `rayon` feature enabled, read-only timers scheduled with [Timers::after_read] that
+//! fire next to each other on the same tick are run in parallel.
+//!
+//! The `audio` feature adds [Timers::play_after] for playing sounds after a delay.
+//!
+//! The `serde` feature makes [TimersSave], the saved state of [Timers], serializable.
 // use bevy::ecs::Stage;
+use bevy::ecs::event::{Events, ManualEventReader};
+use bevy::ecs::system::{CommandQueue, Resource};
 use bevy::prelude::*;
+#[cfg(feature = "rand")]
+use rand::Rng;
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt;
 use std::mem;
 use std::mem::MaybeUninit;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, ThreadId};
+use std::time::Duration;
 
 const MAX_INTERVAL: usize = 64;
 
-type BoxedSystem = Box<dyn FnOnce(&mut World) + Send + Sync>;
+/// The number of bits of the clock covered by a single level of the wheel.
+const SLOT_BITS: usize = 6;
+
+/// The number of levels in the wheel.
+const LEVELS: usize = 4;
+
+/// The default number of pending timers above which a possible leak is reported.
+const DEFAULT_LEAK_THRESHOLD: usize = 100_000;
+
+/// The default number of ticks per second used to convert durations into ticks.
+const DEFAULT_TICK_RATE: f64 = 60.0;
+
+/// Implemented by types that are `Send + Sync`, or by every type when the `single_thread`
+/// feature is enabled. Timer closures must implement it.
+#[cfg(not(feature = "single_thread"))]
+pub trait MaybeSendSync: Send + Sync {}
+
+#[cfg(not(feature = "single_thread"))]
+impl<T: Send + Sync> MaybeSendSync for T {}
+
+/// Implemented by types that are `Send + Sync`, or by every type when the `single_thread`
+/// feature is enabled. Timer closures must implement it.
+#[cfg(feature = "single_thread")]
+pub trait MaybeSendSync {}
+
+#[cfg(feature = "single_thread")]
+impl<T> MaybeSendSync for T {}
+
+/// A boxed timer closure.
+#[cfg(not(feature = "single_thread"))]
+pub type BoxedSystem = Box<dyn FnOnce(&mut World) + Send + Sync>;
+
+/// A boxed timer closure.
+#[cfg(feature = "single_thread")]
+pub type BoxedSystem = Box<dyn FnOnce(&mut World)>;
+
+/// A boxed timer closure sent from another thread through a [TimerSender].
+type SentSystem = Box<dyn FnOnce(&mut World) + Send + Sync>;
+
+type NonSendBoxedSystem = Box<dyn FnOnce(&mut World)>;
+
+/// A boxed closure that can be run more than once.
+#[cfg(not(feature = "single_thread"))]
+pub type BoxedSystemMut = Box<dyn FnMut(&mut World) + Send + Sync>;
+
+/// A boxed closure that can be run more than once.
+#[cfg(feature = "single_thread")]
+pub type BoxedSystemMut = Box<dyn FnMut(&mut World)>;
+
+/// A callback run for each timer moved down a level of a wheel, given the timer, the level
+/// it moved from and to, and the number of ticks it has left.
+#[cfg(not(feature = "single_thread"))]
+type MigrateHook<T> = Box<dyn FnMut(&T, usize, usize, usize) + Send + Sync>;
+
+/// A callback run for each timer moved down a level of a wheel, given the timer, the level
+/// it moved from and to, and the number of ticks it has left.
+#[cfg(feature = "single_thread")]
+type MigrateHook<T> = Box<dyn FnMut(&T, usize, usize, usize)>;
+
+/// A boxed timer closure that only reads the [World], scheduled with [Timers::after_read].
+pub type BoxedReadSystem = Box<dyn FnOnce(&World, &mut Commands) + Send + Sync>;
+
+/// The closure run when a timer fires.
+pub enum TimerSystem {
+    /// A timer that fires once.
+    Once(BoxedSystem),
+    /// A timer that fires once with read-only access to the world.
+    ReadOnly(BoxedReadSystem),
+    /// A timer that fires every `interval` ticks until it is cancelled.
+    Repeating {
+        system: BoxedSystemMut,
+        interval: usize,
+    },
+}
+
+/// A handle to a timer scheduled on a [Timers] resource.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimerHandle(u64);
+
+/// A timer that ran, recorded in the log enabled with [Timers::set_fire_log].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FireRecord {
+    /// The absolute tick on which the timer fired.
+    pub tick: u64,
+    /// The handle of the timer.
+    pub handle: TimerHandle,
+    /// The tag the timer was scheduled with, if any.
+    pub tag: Option<u64>,
+    /// The label the timer was scheduled with by [Timers::after_labeled], if any.
+    pub label: Option<&'static str>,
+}
+
+/// An error returned when a timer cannot be scheduled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimerError {
+    /// The requested interval is longer than the maximum number of ticks a timer can be
+    /// scheduled ahead.
+    IntervalTooLong { max: usize },
+    /// The timers are paused and scheduling while paused has been disallowed with
+    /// [Timers::set_schedule_while_paused].
+    Paused,
+    /// As many timers as allowed by [Timers::set_max_pending] are already pending.
+    Full { max: usize },
+}
+
+impl fmt::Display for TimerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimerError::IntervalTooLong { max } => {
+                write!(f, "timer interval too long (max is {} ticks)", max)
+            }
+            TimerError::Paused => write!(f, "cannot schedule a timer while paused"),
+            TimerError::Full { max } => {
+                write!(f, "too many pending timers (max is {})", max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TimerError {}
+
+/// An error returned by [HandlerRegistry::register] when a handler is already registered
+/// under the name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DuplicateHandler {
+    /// The name the handler was registered under.
+    pub name: String,
+}
+
+impl fmt::Display for DuplicateHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "a timer handler is already registered as {:?}",
+            self.name
+        )
+    }
+}
+
+impl std::error::Error for DuplicateHandler {}
+
+/// A delay that can be converted into a number of ticks, accepted by [Timers::after].
+pub trait IntoTicks {
+    /// Convert the delay into ticks, given the number of ticks per second.
+    fn into_ticks(self, ticks_per_second: f64) -> usize;
+}
+
+impl IntoTicks for usize {
+    fn into_ticks(self, _: f64) -> usize {
+        self
+    }
+}
+
+impl IntoTicks for u32 {
+    fn into_ticks(self, _: f64) -> usize {
+        self as usize
+    }
+}
+
+/// Values that do not fit in a `usize` saturate, so they are rejected as too long rather
+/// than wrapping around to a short delay.
+impl IntoTicks for u64 {
+    fn into_ticks(self, _: f64) -> usize {
+        usize::try_from(self).unwrap_or(usize::MAX)
+    }
+}
+
+/// Implemented so that integer literals without a suffix can be passed to [Timers::after].
+impl IntoTicks for i32 {
+    fn into_ticks(self, _: f64) -> usize {
+        assert!(self >= 0, "timer delay must not be negative");
+        self as usize
+    }
+}
+
+/// Durations are rounded to the nearest tick. Durations too long to count in ticks
+/// saturate to `usize::MAX`.
+impl IntoTicks for Duration {
+    fn into_ticks(self, ticks_per_second: f64) -> usize {
+        (self.as_secs_f64() * ticks_per_second).round() as usize
+    }
+}
+
+/// What the clock does while the primary window is not focused, set with
+/// [Timers::set_unfocused_policy]. Bevy may update the app less often while it is unfocused
+/// or minimized, which slows down a clock that ticks once per update.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnfocusedPolicy {
+    /// Keep ticking once per update, however often the app updates.
+    Ignore,
+    /// Tick at the tick rate (see [Timers::set_tick_rate]) based on the time elapsed between
+    /// updates, so that timers keep elapsing at the intended rate.
+    KeepRate,
+    /// Stop the clock until the window is focused again.
+    Pause,
+}
+
+/// What [Timers::after_keyed] does when a timer is already pending for the key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyPolicy {
+    /// Keep the pending timer and drop the new one.
+    Ignore,
+    /// Cancel the pending timer and schedule the new one.
+    Replace,
+}
+
+/// The phase of a tick in which a timer runs. All of the timers that fire on a tick run
+/// grouped by phase, starting with [Phase::Early], and in the order they were first scheduled
+/// within each phase.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Phase {
+    Early,
+    #[default]
+    Normal,
+    Late,
+}
+
+/// Parameters passed to a named timer handler, typically loaded from a data file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimerParams(pub BTreeMap<String, String>);
+
+impl TimerParams {
+    /// Parse the parameter with the given name, returning `None` if it is missing or fails
+    /// to parse.
+    pub fn get<T: std::str::FromStr>(&self, name: &str) -> Option<T> {
+        self.0.get(name)?.parse().ok()
+    }
+}
+
+/// A timer handler that can be looked up by name.
+pub type TimerHandler = fn(&mut World, &TimerParams);
+
+/// A Bevy resource mapping names to timer handlers, so that timers can be defined in data
+/// files and scheduled with [Timers::after_named].
+#[derive(Default)]
+pub struct HandlerRegistry {
+    handlers: BTreeMap<String, TimerHandler>,
+}
+
+impl HandlerRegistry {
+    /// Register a handler under the given name. Returns an error, keeping the handler
+    /// already registered, if the name is taken, since silently replacing the handler would
+    /// change what saved timers do when they are loaded.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        handler: TimerHandler,
+    ) -> Result<(), DuplicateHandler> {
+        match self.handlers.entry(name.into()) {
+            Entry::Occupied(entry) => Err(DuplicateHandler {
+                name: entry.key().clone(),
+            }),
+            Entry::Vacant(entry) => {
+                entry.insert(handler);
+                Ok(())
+            }
+        }
+    }
+
+    /// Register a handler under the given name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a handler is already registered under the name.
+    pub fn register_or_panic(&mut self, name: impl Into<String>, handler: TimerHandler) {
+        if let Err(err) = self.register(name, handler) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Return the handler registered under the given name.
+    pub fn get(&self, name: &str) -> Option<TimerHandler> {
+        self.handlers.get(name).copied()
+    }
+}
+
+/// A Bevy resource holding the values produced by timers scheduled with
+/// [Timers::after_collect], in the order the timers fired.
+pub struct TimerResults<T>(pub Vec<T>);
+
+impl<T> Default for TimerResults<T> {
+    fn default() -> Self {
+        TimerResults(Vec::new())
+    }
+}
+
+/// A call to [Timers] captured by [Timers::start_recording].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecordedCall {
+    /// A call to [Timers::after_named], along with the handle of the timer it scheduled.
+    AfterNamed {
+        after: usize,
+        handler_name: String,
+        params: TimerParams,
+        handle: TimerHandle,
+    },
+    /// A call to [Timers::cancel].
+    Cancel { handle: TimerHandle },
+}
+
+/// The calls captured by [Timers::start_recording], each with the tick it was made on,
+/// counted from the start of the recording.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Recording(pub Vec<(u64, RecordedCall)>);
+
+/// A scheduled timer and its metadata.
+struct Timer {
+    handle: TimerHandle,
+    phase: Phase,
+    tag: Option<u64>,
+    key: Option<u64>,
+    /// The key passed to [Timers::throttle], which is separate from the other keys.
+    throttle_key: Option<u64>,
+    system: TimerSystem,
+    /// The number of times a repeating timer has fired.
+    repeats: u64,
+    /// Whether the timer runs on the unscaled clock.
+    realtime: bool,
+    /// The handler and parameters of a timer scheduled with [Timers::after_named].
+    named: Option<Box<(String, TimerHandler, TimerParams)>>,
+    /// The label given to [Timers::after_labeled].
+    label: Option<&'static str>,
+}
 
-struct TimingWheel {
-    current_tick: usize,
-    ring: [Vec<(usize, BoxedSystem)>; MAX_INTERVAL],
+/// The timers in a single slot of a wheel, along with the absolute ticks on which they fire.
+#[cfg(not(feature = "smallvec"))]
+type Slot<T> = Vec<(u64, T)>;
+
+/// The timers in a single slot of a wheel, along with the absolute ticks on which they fire.
+/// Most slots hold at most one timer, which is stored inline.
+#[cfg(feature = "smallvec")]
+type Slot<T> = smallvec::SmallVec<[(u64, T); 1]>;
+
+struct TimingWheel<T> {
+    ring: [Slot<T>; MAX_INTERVAL],
 }
 
-impl Default for TimingWheel {
+impl<T> Default for TimingWheel<T> {
     fn default() -> Self {
-        let mut empty = MaybeUninit::<[Vec<_>; MAX_INTERVAL]>::uninit();
-        let p: *mut Vec<BoxedSystem> = unsafe { mem::transmute(&mut empty) };
+        let mut empty = MaybeUninit::<[Slot<T>; MAX_INTERVAL]>::uninit();
+        let p = empty.as_mut_ptr() as *mut Slot<T>;
         for i in 0..MAX_INTERVAL {
             unsafe {
-                p.add(i).write(vec![]);
+                p.add(i).write(Slot::default());
             }
         }
         TimingWheel {
-            current_tick: 0,
             ring: unsafe { empty.assume_init() },
         }
     }
 }
 
-impl TimingWheel {
-    /// Insert the timer into the wheel.
-    fn schedule(&mut self, offset: usize, ticks: usize, timer: BoxedSystem) {
-        self.ring[offset].push((ticks, timer));
+impl<T> TimingWheel<T> {
+    /// Insert the timer into the wheel. The offset must already be reduced to a slot of the
+    /// wheel, which the level math in [TimingWheelHierarchy] always does.
+    fn schedule(&mut self, offset: usize, target: u64, timer: T) {
+        debug_assert!(
+            offset < MAX_INTERVAL,
+            "slot offset {} is out of range for a wheel of {} slots (timer targets tick {})",
+            offset,
+            MAX_INTERVAL,
+            target
+        );
+        self.ring[offset].push((target, timer));
     }
 
-    /// Return all the timers that execute on the current tick, and more the clock
-    /// forward one.
-    fn tick(&mut self) -> Vec<(usize, BoxedSystem)> {
-        let timers = mem::take(&mut self.ring[self.current_tick]);
-        self.current_tick = (self.current_tick + 1) % MAX_INTERVAL;
-        timers
+    /// Remove and return all the timers in the given slot.
+    fn take(&mut self, offset: usize) -> Slot<T> {
+        mem::take(&mut self.ring[offset])
     }
 }
 
-/// A Bevy resource that allows for the scheduling of tick based timers.
-#[derive(Default)]
-pub struct Timers {
+/// A hierarchy of timing wheels. Level `n` holds timers that fire within the next
+/// `64^(n + 1)` ticks, and each of its slots spans `64^n` ticks. When the clock reaches a
+/// slot of a higher level, that slot's timers are cascaded down to the lower levels.
+struct TimingWheelHierarchy<T> {
     /// One frame at 120 fps.
-    level: [TimingWheel; 4],
+    level: [TimingWheel<T>; LEVELS],
     // TODO: Add more levels (if you want to).
+    /// Number of ticks that have elapsed. The current tick of each level is the
+    /// corresponding base-64 digit of this value.
+    elapsed: u64,
+    /// Number of higher level slots that have been cascaded.
+    cascades: u64,
+    /// Number of timers moved down a level by a cascade.
+    migrations: u64,
+    on_migrate: Option<MigrateHook<T>>,
 }
 
-impl Timers {
+impl<T> Default for TimingWheelHierarchy<T> {
+    fn default() -> Self {
+        TimingWheelHierarchy {
+            level: Default::default(),
+            elapsed: 0,
+            cascades: 0,
+            migrations: 0,
+            on_migrate: None,
+        }
+    }
+}
+
+impl<T> TimingWheelHierarchy<T> {
+    /// The largest number of ticks a timer can be scheduled after.
+    const MAX_INTERVAL: usize = MAX_INTERVAL.pow(LEVELS as u32) - 1;
+
     /// Schedule a timer to occur after the given number of ticks have elapsed.
-    pub fn after<S>(&mut self, after: usize, timer: S)
+    fn schedule(&mut self, after: usize, timer: T) {
+        if after > Self::MAX_INTERVAL {
+            panic!("timer interval too long");
+        }
+        self.insert(self.elapsed + after as u64, timer);
+    }
+
+    /// Insert a timer that fires on the given absolute tick, which must not have passed.
+    fn insert(&mut self, target: u64, timer: T) {
+        let level = self.level_for(target);
+        self.schedule_at_level(level, target, timer);
+    }
+
+    /// Return the level a timer firing on the given absolute tick belongs on: the level of
+    /// the most significant base-64 digit in which the target differs from the clock.
+    fn level_for(&self, target: u64) -> usize {
+        let differs = target ^ self.elapsed;
+        (1..LEVELS)
+            .rev()
+            .find(|&level| differs >> (SLOT_BITS * level) != 0)
+            .unwrap_or(0)
+    }
+
+    /// Insert a timer into the slot of the given level that covers its target tick.
+    fn schedule_at_level(&mut self, level: usize, target: u64, timer: T) {
+        let offset = (target >> (SLOT_BITS * level)) as usize % MAX_INTERVAL;
+        self.level[level].schedule(offset, target, timer);
+    }
+
+    /// Return the current tick of the given level.
+    fn current_tick(&self, level: usize) -> usize {
+        (self.elapsed >> (SLOT_BITS * level)) as usize % MAX_INTERVAL
+    }
+
+    /// Return true if the given level wrapped back around to zero on the last tick.
+    fn wrapped(&self, level: usize) -> bool {
+        let period = 1 << (SLOT_BITS * (level + 1));
+        self.elapsed != 0 && self.elapsed & (period - 1) == 0
+    }
+
+    /// Return the highest level that cascades on the current tick, or 0 if none do.
+    fn cascading(&self) -> usize {
+        (1..LEVELS)
+            .take_while(|&level| self.current_tick(level - 1) == 0)
+            .last()
+            .unwrap_or(0)
+    }
+
+    /// Return true if any timer executes on the current tick, including timers that will be
+    /// cascaded down to it.
+    fn fires_next(&self) -> bool {
+        if !self.level[0].ring[self.current_tick(0)].is_empty() {
+            return true;
+        }
+        (1..=self.cascading()).any(|level| {
+            self.level[level].ring[self.current_tick(level)]
+                .iter()
+                .any(|(target, _)| *target == self.elapsed)
+        })
+    }
+
+    /// Return all the timers that execute on the current tick, and move the clock forward
+    /// one.
+    fn tick(&mut self) -> Slot<T> {
+        // Cascade every level whose lower levels have all wrapped around, starting with the
+        // highest so that timers can fall more than one level at once.
+        //
+        // Every timer in a cascading slot shares all of its digits from this level up with
+        // the clock, so it always lands on a lower level. The lower digits of the clock are
+        // all zero here, so a timer that lands in the slot of the current tick of a lower
+        // level is picked up by that level's cascade (or the drain below) on this same tick
+        // rather than a full wrap later.
+        for level in (1..=self.cascading()).rev() {
+            let offset = self.current_tick(level);
+            self.cascades += 1;
+            for (target, timer) in self.level[level].take(offset) {
+                debug_assert!(target >= self.elapsed && self.level_for(target) < level);
+                self.migrations += 1;
+                if self.on_migrate.is_some() {
+                    let to = self.level_for(target);
+                    let remaining = (target - self.elapsed) as usize;
+                    if let Some(hook) = &mut self.on_migrate {
+                        hook(&timer, level, to, remaining);
+                    }
+                }
+                self.insert(target, timer);
+            }
+        }
+        let timers = self.level[0].take(self.current_tick(0));
+        self.elapsed += 1;
+        timers
+    }
+
+    /// Return the slots that may hold a timer firing on the given absolute tick. A timer sits
+    /// on the level returned by `level_for` or, until the next tick cascades it, a level
+    /// above.
+    fn slots_for(&mut self, target: u64) -> impl Iterator<Item = &mut Slot<T>> {
+        let lowest = self.level_for(target);
+        self.level
+            .iter_mut()
+            .enumerate()
+            .skip(lowest)
+            .map(move |(level, wheel)| {
+                let offset = (target >> (SLOT_BITS * level)) as usize % MAX_INTERVAL;
+                &mut wheel.ring[offset]
+            })
+    }
+
+    /// Return the number of pending timers on each level.
+    fn occupancy(&self) -> [usize; LEVELS] {
+        let mut occupancy = [0; LEVELS];
+        for (count, wheel) in occupancy.iter_mut().zip(&self.level) {
+            *count = wheel.ring.iter().map(|slot| slot.len()).sum();
+        }
+        occupancy
+    }
+
+    /// Return the number of pending timers firing on the given absolute tick.
+    fn count_at(&self, target: u64) -> usize {
+        (self.level_for(target)..LEVELS)
+            .map(|level| {
+                let offset = (target >> (SLOT_BITS * level)) as usize % MAX_INTERVAL;
+                self.level[level].ring[offset]
+                    .iter()
+                    .filter(|(other, _)| *other == target)
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Remove and return the pending timer firing on the given absolute tick that matches
+    /// the predicate. The order of the remaining timers is preserved.
+    fn remove_at<F>(&mut self, target: u64, mut predicate: F) -> Option<T>
     where
-        S: FnOnce(&mut World) + Send + Sync + 'static,
+        F: FnMut(&T) -> bool,
     {
-        let ticks = after
-            + self.level[0].current_tick
-            + (self.level[1].current_tick << 6)
-            + (self.level[2].current_tick << 12)
-            + (self.level[3].current_tick << 18);
-        let level = if ticks == 0 {
-            0
-        } else {
-            (63 - ticks.leading_zeros()) / 6
-        };
-        match level {
-            0 => self.level[0].schedule(ticks, 0, Box::new(timer)),
-            1 => self.level[1].schedule((ticks >> 6) - 1, ticks & 0b111111, Box::new(timer)),
-            2 => self.level[2].schedule((ticks >> 12) - 1, ticks & 0b111111111111, Box::new(timer)),
-            3 => self.level[3].schedule(
-                (ticks >> 18) - 1,
-                ticks & 0b111111111111111111,
-                Box::new(timer),
-            ),
-            _ => panic!("timer interval too long"),
+        for slot in self.slots_for(target) {
+            if let Some(i) = slot
+                .iter()
+                .position(|(at, timer)| *at == target && predicate(timer))
+            {
+                return Some(slot.remove(i).1);
+            }
         }
+        None
     }
 
-    /// Schedule a timer to occur right now.
-    pub fn now<S>(&mut self, timer: S)
+    /// Return the pending timer firing on the given absolute tick that matches the predicate.
+    fn get_at<F>(&self, target: u64, mut predicate: F) -> Option<&T>
     where
-        S: FnOnce(&mut World) + Send + Sync + 'static,
+        F: FnMut(&T) -> bool,
     {
-        self.after(0, timer);
+        (self.level_for(target)..LEVELS)
+            .flat_map(|level| {
+                let offset = (target >> (SLOT_BITS * level)) as usize % MAX_INTERVAL;
+                self.level[level].ring[offset].iter()
+            })
+            .find(|(at, timer)| *at == target && predicate(timer))
+            .map(|(_, timer)| timer)
     }
 
-    fn tick(&mut self) -> Vec<BoxedSystem> {
-        // Surely there is a better way to do this.
-        let v = self.level[0].tick().into_iter().map(|(_, x)| x).collect();
-        if self.level[0].current_tick == 63 {
-            for (tick, timer) in self.level[1].tick() {
-                self.level[0].schedule(tick, 0, timer);
-            }
-            if self.level[1].current_tick == 63 {
-                for (tick, timer) in self.level[2].tick() {
-                    self.level[1].schedule((tick >> 6) - 1, tick & 0b111111, timer);
-                }
-                if self.level[2].current_tick == 63 {
-                    for (tick, timer) in self.level[3].tick() {
-                        self.level[2].schedule((tick >> 6) - 1, tick & 0b111111111111, timer);
-                    }
-                }
+    /// Return the pending timer firing on the given absolute tick that matches the
+    /// predicate, mutably.
+    fn get_mut_at<F>(&mut self, target: u64, mut predicate: F) -> Option<&mut T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.slots_for(target)
+            .flat_map(|slot| slot.iter_mut())
+            .find(|(at, timer)| *at == target && predicate(timer))
+            .map(|(_, timer)| timer)
+    }
+
+    /// Remove and return every pending timer that matches the predicate. The order of the
+    /// remaining timers is preserved.
+    fn remove_all<F>(&mut self, mut predicate: F) -> Vec<(u64, T)>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut removed = Vec::new();
+        for slot in self
+            .level
+            .iter_mut()
+            .flat_map(|wheel| wheel.ring.iter_mut())
+        {
+            if slot.iter().any(|(_, timer)| predicate(timer)) {
+                let (matching, kept) = mem::take(slot)
+                    .into_iter()
+                    .partition(|(_, timer)| predicate(timer));
+                *slot = kept;
+                removed.extend::<Slot<T>>(matching);
             }
         }
-        v
+        removed
+    }
+
+    /// Return true if no timers are pending in the hierarchy.
+    fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// Iterate over every pending timer in the hierarchy along with the absolute tick on
+    /// which it fires.
+    fn iter(&self) -> impl Iterator<Item = (u64, &T)> {
+        self.level
+            .iter()
+            .flat_map(|wheel| wheel.ring.iter())
+            .flat_map(|slot| slot.iter().map(|(target, timer)| (*target, timer)))
+    }
+
+    /// Iterate mutably over every pending timer, in no particular order.
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.level
+            .iter_mut()
+            .flat_map(|wheel| wheel.ring.iter_mut())
+            .flat_map(|slot| slot.iter_mut().map(|(_, timer)| timer))
+    }
+
+    /// Remove every pending timer from the hierarchy, highest level first. The clock is
+    /// left untouched.
+    fn drain(&mut self) -> Vec<(u64, T)> {
+        self.level
+            .iter_mut()
+            .rev()
+            .flat_map(|wheel| wheel.ring.iter_mut())
+            .flat_map(mem::take)
+            .collect()
     }
 }
 
-#[derive(Default)]
-struct RunTimers;
+/// A timer removed from a [Timers] resource by [Timers::extract].
+pub struct PendingTimer {
+    /// The handle of the timer, which it keeps in [Timers::from_pending].
+    pub handle: TimerHandle,
+    /// The number of ticks left until the timer fires.
+    pub remaining: usize,
+    /// The phase of the tick the timer runs in.
+    pub phase: Phase,
+    /// The tag the timer was scheduled with, if any.
+    pub tag: Option<u64>,
+    /// The key the timer was scheduled under, e.g. by [Timers::after_keyed], if any.
+    pub key: Option<u64>,
+    /// The key the timer was scheduled under by [Timers::throttle], if any.
+    pub throttle_key: Option<u64>,
+    /// The label given to [Timers::after_labeled], if any.
+    pub label: Option<&'static str>,
+    /// The handler and parameters of a timer scheduled with [Timers::after_named].
+    pub named: Option<(String, TimerHandler, TimerParams)>,
+    /// The number of times a repeating timer has fired, counted against the limit of
+    /// [Timers::set_max_repeats].
+    pub repeats: u64,
+    /// Whether the timer was scheduled with [Timers::after_realtime].
+    pub realtime: bool,
+    /// The timer itself.
+    pub system: TimerSystem,
+}
 
-impl Stage for RunTimers {
-    fn run(&mut self, world: &mut World) {
-        let timers = world.get_resource_mut::<Timers>().expect("Failed").tick();
-        for timer in timers {
-            timer(world);
+/// The pending timers of a [Timers] resource, in the order in which they would have fired.
+///
+/// Timers can be extracted from one [Timers] resource and re-inserted into a fresh one with
+/// [Timers::from_pending], for example when hot-reloading gameplay logic.
+pub struct PendingTimers(pub Vec<PendingTimer>);
+
+/// The clocks and named timers of a [Timers] resource, saved with [Timers::save] and
+/// restored with [Timers::from_save]. With the `serde` feature enabled this can be
+/// serialized, e.g. as part of a save game.
+///
+/// The position of each level of the timing wheel follows from the elapsed tick, so
+/// restoring the elapsed ticks puts the wheel back exactly where it was.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimersSave {
+    /// The value of [Timers::elapsed_ticks].
+    pub elapsed: u64,
+    /// The number of ticks the clock of [Timers::after_realtime] has run for.
+    pub realtime_elapsed: u64,
+    /// The value of [Timers::tick_accumulator].
+    pub scaled_behind: f64,
+    /// The epochs marked with [Timers::mark_epoch], and the tick each was marked on.
+    pub epochs: BTreeMap<String, u64>,
+    /// The pending timers scheduled with [Timers::after_named], in the order they were
+    /// scheduled.
+    pub timers: Vec<SavedTimer>,
+}
+
+/// A timer scheduled with [Timers::after_named], as saved in a [TimersSave].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SavedTimer {
+    /// The absolute tick on which the timer fires, on the clock it was scheduled on.
+    pub target: u64,
+    /// Whether the timer runs on the clock of [Timers::after_realtime].
+    pub realtime: bool,
+    /// The phase of the tick the timer runs in.
+    pub phase: Phase,
+    /// The tag the timer was scheduled with, if any.
+    pub tag: Option<u64>,
+    /// The name the handler is registered under in the [HandlerRegistry].
+    pub handler_name: String,
+    /// The parameters the handler is called with.
+    pub params: TimerParams,
+}
+
+/// A handle for scheduling timers on a [Timers] resource from other threads, such as async
+/// tasks, that do not have access to the [World]. Obtained from [Timers::sender].
+///
+/// Timers sent this way are added to the wheel at the start of the next tick, and their
+/// delay is counted from then.
+#[derive(Clone)]
+pub struct TimerSender {
+    sender: Sender<(usize, SentSystem)>,
+}
+
+impl TimerSender {
+    /// Schedule a timer to occur after the given number of ticks have elapsed. Returns
+    /// false if the [Timers] resource no longer exists.
+    pub fn after<S>(&self, after: usize, timer: S) -> bool
+    where
+        S: FnOnce(&mut World) + Send + Sync + 'static,
+    {
+        self.sender.send((after, Box::new(timer))).is_ok()
+    }
+}
+
+/// A Bevy resource that allows for the scheduling of tick based timers.
+pub struct Timers {
+    wheel: TimingWheelHierarchy<Timer>,
+    /// The wheel of timers scheduled with [Timers::after_realtime], which is not affected by
+    /// the time scale.
+    realtime: TimingWheelHierarchy<Timer>,
+    time_scale: f64,
+    /// The fraction of a scaled tick carried over from previous real ticks.
+    scaled_behind: f64,
+    next_handle: u64,
+    /// The pending timer scheduled for each key by [Timers::after_keyed].
+    keyed: BTreeMap<u64, TimerHandle>,
+    /// The tick until which new timers for each key passed to [Timers::throttle] are held
+    /// back, for the keys whose last timer fired less than its minimum interval ago.
+    throttled: BTreeMap<u64, u64>,
+    /// The entries of `throttled` ordered by the tick they expire on, so that they can be
+    /// removed once they no longer hold anything back.
+    throttle_expiry: BTreeSet<(u64, u64)>,
+    /// The pending timer scheduled for each key by [Timers::throttle].
+    throttle_pending: BTreeMap<u64, TimerHandle>,
+    /// The tick on which each epoch was marked by [Timers::mark_epoch].
+    epochs: BTreeMap<String, u64>,
+    key_policy: KeyPolicy,
+    /// The absolute tick on which each pending timer fires, used to find it in the wheel
+    /// without scanning every slot.
+    targets: BTreeMap<TimerHandle, u64>,
+    /// The timers paused by [Timers::pause_group], along with the number of ticks each had
+    /// left, keyed by tag.
+    frozen: BTreeMap<u64, Vec<(usize, Timer)>>,
+    /// Timers that fired on the current tick and have yet to run.
+    in_flight: BTreeSet<TimerHandle>,
+    /// Timers that were cancelled after firing but before they ran.
+    skipped: BTreeSet<TimerHandle>,
+    /// Callbacks run when a level wraps around, along with the level they watch.
+    wrap_hooks: Vec<(usize, BoxedSystemMut)>,
+    /// Callbacks run at the start of the next tick by [Timers::on_first_tick].
+    first_tick_hooks: Vec<BoxedSystem>,
+    max_repeats: Option<u64>,
+    catch_up_rate: Option<f64>,
+    max_catch_up: Option<usize>,
+    /// The number of ticks owed when catching up on missed ticks.
+    behind: f64,
+    tick_budget: Option<usize>,
+    /// The number of timers run so far in the current batch, checked against the budget.
+    batch_ran: usize,
+    unfocused_policy: UnfocusedPolicy,
+    /// Whether the primary window was focused on the last update, or true without a window.
+    focused: bool,
+    sender: Sender<(usize, SentSystem)>,
+    receiver: Mutex<Receiver<(usize, SentSystem)>>,
+    /// Number of timers that have been scheduled but have not yet run.
+    pending: usize,
+    total_scheduled: u64,
+    total_fired: u64,
+    /// Number of times any timer has run, including every run of a repeating timer.
+    fires: u64,
+    total_cancelled: u64,
+    leak_threshold: Option<usize>,
+    leak_warned: bool,
+    max_pending: Option<usize>,
+    tick_rate: f64,
+    paused: bool,
+    schedule_while_paused: bool,
+    immediate_mode: bool,
+    /// True while the timers that fired on a tick are being run.
+    draining: bool,
+    fire_log: Vec<FireRecord>,
+    /// The maximum length of the fire log, or `None` if the log is disabled.
+    fire_log_cap: Option<usize>,
+    /// The calls recorded so far, along with the tick the recording started on.
+    recording: Option<(u64, Recording)>,
+    /// The calls of a recording left to replay, on absolute ticks.
+    replaying: VecDeque<(u64, RecordedCall)>,
+    /// The handles of the timers scheduled by a replay, keyed by their recorded handles.
+    replayed: BTreeMap<TimerHandle, TimerHandle>,
+}
+
+impl Default for Timers {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Timers {
+            wheel: TimingWheelHierarchy::default(),
+            realtime: TimingWheelHierarchy::default(),
+            time_scale: 1.0,
+            scaled_behind: 0.0,
+            next_handle: 0,
+            keyed: BTreeMap::new(),
+            throttled: BTreeMap::new(),
+            throttle_expiry: BTreeSet::new(),
+            throttle_pending: BTreeMap::new(),
+            epochs: BTreeMap::new(),
+            key_policy: KeyPolicy::Ignore,
+            targets: BTreeMap::new(),
+            frozen: BTreeMap::new(),
+            in_flight: BTreeSet::new(),
+            skipped: BTreeSet::new(),
+            wrap_hooks: Vec::new(),
+            first_tick_hooks: Vec::new(),
+            max_repeats: None,
+            catch_up_rate: None,
+            max_catch_up: None,
+            behind: 0.0,
+            tick_budget: None,
+            unfocused_policy: UnfocusedPolicy::Ignore,
+            focused: true,
+            sender,
+            receiver: Mutex::new(receiver),
+            pending: 0,
+            total_scheduled: 0,
+            total_fired: 0,
+            fires: 0,
+            total_cancelled: 0,
+            leak_threshold: Some(DEFAULT_LEAK_THRESHOLD),
+            leak_warned: false,
+            max_pending: None,
+            tick_rate: DEFAULT_TICK_RATE,
+            paused: false,
+            schedule_while_paused: true,
+            immediate_mode: false,
+            draining: false,
+            batch_ran: 0,
+            fire_log: Vec::new(),
+            fire_log_cap: None,
+            recording: None,
+            replaying: VecDeque::new(),
+            replayed: BTreeMap::new(),
         }
     }
 }
 
-/// A Bevy plugin that adds the [Timers] resource and a scheduler to execute timers each
-/// game update.
-pub struct TimerPlugin;
+impl Timers {
+    /// Set the number of pending timers above which a warning is logged, or `None` to
+    /// disable the check. Unbounded growth of pending timers usually indicates a leak.
+    ///
+    /// The check only runs in debug builds. Defaults to 100,000.
+    pub fn set_leak_threshold(&mut self, threshold: Option<usize>) {
+        self.leak_threshold = threshold;
+        self.leak_warned = false;
+    }
 
-impl Plugin for TimerPlugin {
-    fn build(&self, app: &mut App) {
-        app.world.insert_resource(Timers::default());
-        app.add_stage("run_timers", RunTimers);
+    /// Enable recording every timer that runs to the fire log, keeping at most `cap`
+    /// records, or disable it with `None`. Once the log is full, further records are
+    /// dropped until it is cleared with [Timers::clear_log]. Disabled by default.
+    ///
+    /// Two runs of the same deterministic simulation produce identical logs, which makes
+    /// the log useful for detecting divergence.
+    pub fn set_fire_log(&mut self, cap: Option<usize>) {
+        self.fire_log_cap = cap;
+    }
+
+    /// Return the records in the fire log, oldest first.
+    pub fn fire_log(&self) -> &[FireRecord] {
+        &self.fire_log
+    }
+
+    /// Remove every record from the fire log.
+    pub fn clear_log(&mut self) {
+        self.fire_log.clear();
+    }
+
+    /// Start recording calls to [Timers::after_named] and [Timers::cancel], along with the
+    /// tick each call is made on, replacing any recording in progress. Timers cancelled in
+    /// bulk, e.g. by [Timers::cancel_tag], are recorded as one cancel per timer. Timers
+    /// scheduled with closures cannot be recorded, so only named timers are reproduced by a
+    /// replay.
+    pub fn start_recording(&mut self) {
+        self.recording = Some((self.wheel.elapsed, Recording::default()));
+    }
+
+    /// Stop recording and return the calls recorded since [Timers::start_recording], or
+    /// `None` if no recording was in progress.
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        self.recording.take().map(|(_, recording)| recording)
+    }
+
+    /// Re-issue the calls of a recording, for example against a fresh [Timers] to reproduce
+    /// a desync. Each call is made on the same tick relative to now as it was made relative
+    /// to the start of the recording, before the timers of the next tick run, so a call made
+    /// by a timer is re-issued once the rest of its tick has run. Cancelling a timer that
+    /// was not scheduled by the recording is ignored. The named handlers are looked up in
+    /// the [HandlerRegistry] when each call is re-issued.
+    pub fn replay(&mut self, recording: Recording) {
+        let now = self.wheel.elapsed;
+        self.replaying.extend(
+            recording
+                .0
+                .into_iter()
+                .map(|(tick, call)| (now + tick, call)),
+        );
+    }
+
+    /// Schedule a timer to occur after the given number of ticks have elapsed. The delay
+    /// may also be given as a [Duration], which is converted using the tick rate.
+    pub fn after<D, S>(&mut self, after: D, timer: S) -> TimerHandle
+    where
+        D: IntoTicks,
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        let after = after.into_ticks(self.tick_rate);
+        let timer = self.new_timer(TimerSystem::Once(Box::new(timer)));
+        self.schedule(after, timer)
+    }
+
+    /// Set the number of ticks per second used to convert a [Duration] into ticks.
+    /// Defaults to 60.
+    pub fn set_tick_rate(&mut self, ticks_per_second: f64) {
+        self.tick_rate = ticks_per_second;
+    }
+
+    /// Return the number of ticks per second used to convert a [Duration] into ticks.
+    pub fn tick_rate(&self) -> f64 {
+        self.tick_rate
+    }
+
+    /// Schedule a timer to occur after roughly the given number of real seconds, judged by
+    /// how fast the clock is actually ticking rather than by the tick rate.
+    ///
+    /// With a catch-up rate set (see [Timers::set_catch_up]) the clock ticks at that rate,
+    /// so the delay is `secs * rate` ticks. Otherwise the clock ticks once per update and
+    /// the delay is `secs / delta` ticks, where `delta` is the length of the last frame
+    /// according to `time`. If the last frame took no time, as on the first update, the
+    /// tick rate is used instead. Either way the delay is then multiplied by the time scale
+    /// and rounded to the nearest tick.
+    pub fn after_real_secs<S>(&mut self, time: &Time, secs: f32, timer: S) -> TimerHandle
+    where
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        let delta = time.delta_seconds_f64();
+        let ticks_per_second = match self.catch_up_rate {
+            Some(rate) => rate,
+            None if delta > 0.0 => delta.recip(),
+            None => self.tick_rate,
+        };
+        let after = (secs as f64 * ticks_per_second * self.time_scale).round() as usize;
+        self.after(after, timer)
+    }
+
+    /// Schedule a timer to occur after the given number of physics steps have elapsed, when
+    /// the clock ticks once per physics step with [TimerPlugin::physics_stepped]. This is
+    /// the same as [Timers::after], but says what the delay is counted in.
+    pub fn after_physics_steps<S>(&mut self, steps: usize, timer: S) -> TimerHandle
+    where
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        self.after(steps, timer)
+    }
+
+    /// Return the number of ticks left before a pending timer fires, as the delay that would
+    /// be passed to [Timers::after] to schedule a timer firing at the same time. Returns
+    /// `None` if the timer has already run or been cancelled.
+    pub fn remaining(&self, handle: TimerHandle) -> Option<usize> {
+        let target = *self.targets.get(&handle)?;
+        let realtime = self
+            .realtime
+            .get_at(target, |timer| timer.handle == handle)
+            .is_some();
+        Some(target.saturating_sub(self.clock(realtime).elapsed) as usize)
+    }
+
+    /// Return the time left before a pending timer fires in seconds, converted from
+    /// [Timers::remaining] using the tick rate, e.g. to display a cooldown. Returns `None`
+    /// if the timer has already run or been cancelled.
+    pub fn remaining_secs(&self, handle: TimerHandle) -> Option<f32> {
+        self.remaining(handle)
+            .map(|ticks| (ticks as f64 / self.tick_rate) as f32)
+    }
+
+    /// Schedule a timer to occur after the given number of ticks have elapsed, returning an
+    /// error instead of panicking if the timer cannot be scheduled.
+    pub fn after_checked<S>(&mut self, after: usize, timer: S) -> Result<TimerHandle, TimerError>
+    where
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        self.try_after(after, timer)
+    }
+
+    /// Schedule a timer to occur after the given delay, returning an error instead of
+    /// panicking if the timer cannot be scheduled, either because the delay is too long,
+    /// because the timers are paused and scheduling while paused is disallowed, or because
+    /// the limit set with [Timers::set_max_pending] has been reached.
+    pub fn try_after<D, S>(&mut self, after: D, timer: S) -> Result<TimerHandle, TimerError>
+    where
+        D: IntoTicks,
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        let after = after.into_ticks(self.tick_rate);
+        let max = TimingWheelHierarchy::<Timer>::MAX_INTERVAL;
+        if after > max {
+            return Err(TimerError::IntervalTooLong { max });
+        }
+        if self.paused && !self.schedule_while_paused {
+            return Err(TimerError::Paused);
+        }
+        if let Some(max) = self.max_pending.filter(|&max| self.pending >= max) {
+            return Err(TimerError::Full { max });
+        }
+        let timer = self.new_timer(TimerSystem::Once(Box::new(timer)));
+        Ok(self.schedule(after, timer))
+    }
+
+    /// Stop the clock. No timers fire, and the clock does not advance, until
+    /// [Timers::resume] is called.
+    ///
+    /// Timers scheduled while paused are scheduled relative to the frozen clock, so even a
+    /// timer scheduled after zero ticks does not fire until the timers are resumed.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Restart the clock after [Timers::pause].
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Return true if the clock is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Set whether timers may be scheduled while paused. When disallowed, scheduling a timer
+    /// while paused panics, or returns [TimerError::Paused] from [Timers::try_after].
+    /// Allowed by default.
+    pub fn set_schedule_while_paused(&mut self, allowed: bool) {
+        self.schedule_while_paused = allowed;
+    }
+
+    /// Set the most timers that may be pending at once, or `None` for no limit, e.g. to stop
+    /// clients of a server from flooding it with timers. Once the limit is reached,
+    /// [Timers::try_after] and [Timers::after_checked] return [TimerError::Full], and every
+    /// other way of scheduling a timer drops it with a warning and returns a handle that is
+    /// never pending, until a pending timer fires or is cancelled. [Timers::pending_count]
+    /// gives the number of pending timers. Repeating timers that reschedule themselves are
+    /// never limited. Defaults to no limit.
+    pub fn set_max_pending(&mut self, max: Option<usize>) {
+        self.max_pending = max;
+    }
+
+    /// Return the longest interval, in ticks, that a timer can be scheduled after.
+    pub fn max_interval(&self) -> u64 {
+        TimingWheelHierarchy::<Timer>::MAX_INTERVAL as u64
+    }
+
+    /// Return the number of levels in the timing wheel.
+    pub fn levels(&self) -> usize {
+        LEVELS
+    }
+
+    /// Return the number of pending timers on each level of the timing wheel, including the
+    /// timers of [Timers::after_realtime]. A timer waits on a higher level until it cascades
+    /// down, so most timers piling up on the highest levels means most delays are long.
+    pub fn level_occupancy(&self) -> [usize; LEVELS] {
+        let mut occupancy = self.wheel.occupancy();
+        for (count, realtime) in occupancy.iter_mut().zip(self.realtime.occupancy()) {
+            *count += realtime;
+        }
+        occupancy
+    }
+
+    /// Set the number of ticks the clock advances by for each real tick, e.g. `0.5` for slow
+    /// motion. Fractional ticks are carried over to later real ticks. Timers scheduled with
+    /// [Timers::after_realtime] are not affected. Defaults to 1.
+    pub fn set_time_scale(&mut self, scale: f64) {
+        self.time_scale = scale;
+    }
+
+    /// Return the number of ticks the clock advances by for each real tick.
+    pub fn time_scale(&self) -> f64 {
+        self.time_scale
+    }
+
+    /// Return the fraction of a tick carried over by the time scale, which is added to the
+    /// next real tick. This explains why a fractional time scale sometimes runs one tick more
+    /// or less than expected on a real tick.
+    pub fn tick_accumulator(&self) -> f64 {
+        self.scaled_behind
+    }
+
+    /// Schedule a timer to occur after the given number of real ticks have elapsed,
+    /// regardless of the time scale, e.g. for UI animations that should not slow down with
+    /// the game.
+    ///
+    /// Realtime timers run on their own clock, before the timers of the scaled clock. They
+    /// can be cancelled and retagged like any other timer, but are not included by queries
+    /// about upcoming ticks, such as [Timers::for_each_pending].
+    pub fn after_realtime<S>(&mut self, after: usize, timer: S) -> TimerHandle
+    where
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        let timer = Timer {
+            realtime: true,
+            ..self.new_timer(TimerSystem::Once(Box::new(timer)))
+        };
+        self.schedule(after, timer)
+    }
+
+    /// Return the number of ticks that have elapsed on the clock.
+    pub fn elapsed_ticks(&self) -> u64 {
+        self.wheel.elapsed
+    }
+
+    /// Set the clock to the given tick, e.g. to match an authoritative server on connect.
+    /// Pending timers keep the absolute tick they fire on, so a timer whose tick has already
+    /// passed on the new clock fires on the next tick. When moving the clock backwards, a
+    /// timer is never left more than [Timers::max_interval] ticks away.
+    pub fn set_elapsed_ticks(&mut self, ticks: u64) {
+        let timers = self.wheel.drain();
+        self.wheel.elapsed = ticks;
+        let max = TimingWheelHierarchy::<Timer>::MAX_INTERVAL as u64;
+        for (target, timer) in timers {
+            self.insert(target.clamp(ticks, ticks + max), timer);
+        }
+    }
+
+    /// Return the current slot of the given level of the timing wheel, which is the
+    /// level's digit of [Timers::elapsed_ticks] in base 64.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `level` is not less than [Timers::levels].
+    pub fn level_tick(&self, level: usize) -> usize {
+        assert!(level < LEVELS, "level out of range");
+        (self.wheel.elapsed >> (SLOT_BITS * level)) as usize % MAX_INTERVAL
+    }
+
+    /// Set the current slot of the given level of the timing wheel, leaving the other levels
+    /// as they are. This moves the clock like [Timers::set_elapsed_ticks], so pending timers
+    /// are rescheduled the same way. Does nothing and returns false if `level` is not less
+    /// than [Timers::levels] or `tick` is not less than 64.
+    pub fn set_level_tick(&mut self, level: usize, tick: usize) -> bool {
+        if level >= LEVELS || tick >= MAX_INTERVAL {
+            return false;
+        }
+        let shift = SLOT_BITS * level;
+        let mask = (MAX_INTERVAL as u64 - 1) << shift;
+        let elapsed = (self.wheel.elapsed & !mask) | (tick as u64) << shift;
+        self.set_elapsed_ticks(elapsed);
+        true
+    }
+
+    /// Schedule a timer to occur after a number of ticks picked uniformly at random from
+    /// `min..=max`. The random number generator is supplied by the caller so that replays
+    /// stay deterministic when it is seeded.
+    #[cfg(feature = "rand")]
+    pub fn after_ranged<R, S>(
+        &mut self,
+        min: usize,
+        max: usize,
+        rng: &mut R,
+        timer: S,
+    ) -> TimerHandle
+    where
+        R: Rng + ?Sized,
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        self.after(rng.gen_range(min..=max), timer)
+    }
+
+    /// Schedule a timer to occur after whichever number of ticks in `min..=max` has the
+    /// fewest timers already firing on it, preferring the earliest on a tie. This spreads
+    /// out timers whose exact timing is flexible to avoid spikes.
+    pub fn after_least_loaded<S>(&mut self, min: usize, max: usize, timer: S) -> TimerHandle
+    where
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        let max = max
+            .min(TimingWheelHierarchy::<Timer>::MAX_INTERVAL)
+            .max(min);
+        let after = (min..=max)
+            .min_by_key(|&after| self.load_at(after))
+            .unwrap_or(min);
+        self.after(after, timer)
+    }
+
+    /// Return the number of pending timers that will fire after the given number of ticks,
+    /// as a timer scheduled with `after(ticks_from_now, ..)` would.
+    pub fn load_at(&self, ticks_from_now: usize) -> usize {
+        self.wheel
+            .count_at(self.wheel.elapsed.saturating_add(ticks_from_now as u64))
+    }
+
+    /// Schedule a timer to occur after the given number of ticks have elapsed, grouped
+    /// under the given tag.
+    pub fn after_tagged<S>(&mut self, after: usize, tag: u64, timer: S) -> TimerHandle
+    where
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        let timer = Timer {
+            tag: Some(tag),
+            ..self.new_timer(TimerSystem::Once(Box::new(timer)))
+        };
+        self.schedule(after, timer)
+    }
+
+    /// Schedule a wave of timers spread evenly over the next `window` ticks, all grouped under
+    /// the given tag, e.g. to stagger the spawns of a wave of enemies. Of `n` timers, the
+    /// `i`th occurs after `i * window / n` ticks, so the first occurs on the next tick. The
+    /// rest of the wave can be cancelled with [Timers::cancel_tag] or paused with
+    /// [Timers::pause_group]. Returns the handles of the timers in order.
+    pub fn spawn_wave<I, S>(&mut self, tag: u64, window: usize, entries: I) -> Vec<TimerHandle>
+    where
+        I: IntoIterator<Item = S>,
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        let entries: Vec<_> = entries.into_iter().collect();
+        let count = entries.len();
+        entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, timer)| self.after_tagged(i * window / count, tag, timer))
+            .collect()
+    }
+
+    /// Schedule a timer to occur after the given number of ticks have elapsed, with a label
+    /// such as `"player_regen"` that is recorded in the fire log (see [Timers::set_fire_log])
+    /// to tell timers apart when diagnosing them.
+    pub fn after_labeled<S>(&mut self, after: usize, label: &'static str, timer: S) -> TimerHandle
+    where
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        let timer = Timer {
+            label: Some(label),
+            ..self.new_timer(TimerSystem::Once(Box::new(timer)))
+        };
+        self.schedule(after, timer)
+    }
+
+    /// Schedule a timer to occur after the given number of ticks have elapsed, in the given
+    /// phase of that tick. Timers scheduled any other way run in [Phase::Normal].
+    pub fn after_phased<S>(&mut self, after: usize, phase: Phase, timer: S) -> TimerHandle
+    where
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        let timer = Timer {
+            phase,
+            ..self.new_timer(TimerSystem::Once(Box::new(timer)))
+        };
+        self.schedule(after, timer)
+    }
+
+    /// Record the current tick under the given name, replacing any earlier mark, so that
+    /// timers can later be scheduled relative to it with [Timers::after_epoch].
+    pub fn mark_epoch(&mut self, name: &str) {
+        self.epochs.insert(name.to_string(), self.wheel.elapsed);
+    }
+
+    /// Schedule a timer to occur `offset` ticks after the named epoch was marked, as if it
+    /// had been scheduled with [Timers::after] when the epoch was marked. If that tick has
+    /// already passed, the timer occurs on the next tick. Returns `None` without scheduling
+    /// anything if no epoch has been marked under the name.
+    pub fn after_epoch<S>(&mut self, epoch: &str, offset: usize, timer: S) -> Option<TimerHandle>
+    where
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        let target = self.epochs.get(epoch)?.saturating_add(offset as u64);
+        let after = target.saturating_sub(self.wheel.elapsed) as usize;
+        Some(self.after(after, timer))
+    }
+
+    /// Schedule a timer that only needs to read the [World] to occur after the given number
+    /// of ticks have elapsed. Changes to the world are made through the [Commands], which
+    /// are applied once the timer has run.
+    ///
+    /// Read-only timers that fire next to each other on the same tick, with no timer taking
+    /// the world mutably in between, form a group. With the `rayon` feature enabled, the
+    /// timers of a group are run in parallel, so they must not rely on running in any
+    /// particular order. The commands of a group are applied after all of its timers have
+    /// run, in the order the timers were scheduled.
+    pub fn after_read<S>(&mut self, after: usize, timer: S) -> TimerHandle
+    where
+        S: FnOnce(&World, &mut Commands) + Send + Sync + 'static,
+    {
+        let timer = self.new_timer(TimerSystem::ReadOnly(Box::new(timer)));
+        self.schedule(after, timer)
+    }
+
+    /// Schedule the handler registered under the given name to be called with the given
+    /// parameters after the given number of ticks have elapsed. If no handler is registered
+    /// under the name, an error is logged and nothing is scheduled.
+    pub fn after_named(
+        &mut self,
+        registry: &HandlerRegistry,
+        after: usize,
+        handler_name: &str,
+        params: TimerParams,
+    ) -> Option<TimerHandle> {
+        let handler = match registry.get(handler_name) {
+            Some(handler) => handler,
+            None => {
+                error!("no timer handler named {:?} is registered", handler_name);
+                return None;
+            }
+        };
+        let recorded = self.recording.is_some().then(|| params.clone());
+        let timer = self.named_timer(handler_name, handler, params);
+        let handle = self.schedule(after, timer);
+        if let Some(params) = recorded {
+            self.record(RecordedCall::AfterNamed {
+                after,
+                handler_name: handler_name.to_string(),
+                params,
+                handle,
+            });
+        }
+        Some(handle)
+    }
+
+    /// Schedule a timer that is given mutable access to a component of an entity. When the
+    /// timer fires it only runs if the entity still exists and still has the component.
+    ///
+    /// The timer is not given the [World], as it cannot be borrowed at the same time as the
+    /// component.
+    pub fn after_component<C, S>(&mut self, after: usize, entity: Entity, timer: S) -> TimerHandle
+    where
+        C: Component,
+        S: FnOnce(Entity, Mut<C>) + MaybeSendSync + 'static,
+    {
+        self.after(after, move |world| {
+            if let Some(component) = world.get_mut::<C>(entity) {
+                timer(entity, component);
+            }
+        })
+    }
+
+    /// Spawn an entity after the given number of ticks have elapsed, and then call `then`
+    /// with it. The bundle is created when the timer fires rather than when it is
+    /// scheduled, so that it can reflect the state of the world at that point.
+    pub fn spawn_after<B, F, T>(&mut self, after: usize, bundle: F, then: T) -> TimerHandle
+    where
+        B: Bundle,
+        F: FnOnce() -> B + MaybeSendSync + 'static,
+        T: FnOnce(&mut World, Entity) + MaybeSendSync + 'static,
+    {
+        self.after(after, move |world: &mut World| {
+            let entity = world.spawn().insert_bundle(bundle()).id();
+            then(world, entity);
+        })
+    }
+
+    /// Despawn an entity after the given number of ticks have elapsed, e.g. to clean up a
+    /// projectile or particle, unless it has already been despawned by then. Cancel the
+    /// returned handle to keep the entity alive.
+    pub fn despawn_after(&mut self, entity: Entity, after: usize) -> TimerHandle {
+        self.after(after, move |world: &mut World| {
+            world.despawn(entity);
+        })
+    }
+
+    /// Play a sound through Bevy's [Audio] resource after the given number of ticks have
+    /// elapsed. A sound that has not finished loading by then is kept queued by Bevy and
+    /// played once it has loaded. Requires the `audio` feature.
+    #[cfg(feature = "audio")]
+    pub fn play_after(&mut self, after: usize, sound: Handle<AudioSource>) -> TimerHandle {
+        self.after(after, move |world: &mut World| {
+            match world.get_resource::<Audio>() {
+                Some(audio) => audio.play(sound),
+                None => warn!("cannot play a sound without an Audio resource"),
+            }
+        })
+    }
+
+    /// Schedule a timer that is given mutable access to a resource. When the timer fires it
+    /// only runs if the resource still exists.
+    pub fn after_resource<R, S>(&mut self, after: usize, timer: S) -> TimerHandle
+    where
+        R: Resource,
+        S: FnOnce(&mut World, Mut<R>) + MaybeSendSync + 'static,
+    {
+        self.after(after, move |world: &mut World| {
+            if world.contains_resource::<R>() {
+                world.resource_scope(timer);
+            }
+        })
+    }
+
+    /// Schedule a timer that produces a value, which is pushed onto the [TimerResults]
+    /// resource for its type when the timer fires. The resource is added if it is missing,
+    /// and is left for a system to drain.
+    pub fn after_collect<T, S>(&mut self, after: usize, timer: S) -> TimerHandle
+    where
+        T: Resource,
+        S: FnOnce(&mut World) -> T + MaybeSendSync + 'static,
+    {
+        self.after(after, move |world: &mut World| {
+            let result = timer(world);
+            match world.get_resource_mut::<TimerResults<T>>() {
+                Some(mut results) => results.0.push(result),
+                None => world.insert_resource(TimerResults(vec![result])),
+            }
+        })
+    }
+
+    /// Schedule a fallible timer to occur after the given number of ticks have elapsed. If
+    /// the timer returns an error when it fires, the error is logged.
+    pub fn after_result<E, S>(&mut self, after: usize, timer: S) -> TimerHandle
+    where
+        E: fmt::Debug,
+        S: FnOnce(&mut World) -> Result<(), E> + MaybeSendSync + 'static,
+    {
+        self.after(after, move |world: &mut World| {
+            if let Err(err) = timer(world) {
+                error!("timer failed: {:?}", err);
+            }
+        })
+    }
+
+    /// Schedule a timer to occur the given number of ticks after another pending timer fires.
+    /// Returns `None` if the base timer has already run or been cancelled. A base timer that
+    /// fired on the current tick but has yet to run still counts, and if the timer would
+    /// fire on a tick that has already passed, it fires on the next tick instead.
+    pub fn after_timer<S>(
+        &mut self,
+        base: TimerHandle,
+        extra: usize,
+        timer: S,
+    ) -> Option<TimerHandle>
+    where
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        let target = *self.targets.get(&base)?;
+        let realtime = self
+            .realtime
+            .get_mut_at(target, |timer| timer.handle == base)
+            .is_some();
+        let after = target
+            .saturating_add(extra as u64)
+            .saturating_sub(self.clock(realtime).elapsed) as usize;
+        let timer = Timer {
+            realtime,
+            ..self.new_timer(TimerSystem::Once(Box::new(timer)))
+        };
+        Some(self.schedule(after, timer))
+    }
+
+    /// Schedule a timer to occur after the given number of ticks have elapsed, unless a
+    /// timer scheduled with the same key is still pending. What happens in that case is
+    /// decided by the [KeyPolicy] set with [Timers::set_key_policy]; by default the new
+    /// timer is ignored and the handle of the pending one is returned.
+    pub fn after_keyed<S>(&mut self, key: u64, after: usize, timer: S) -> TimerHandle
+    where
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        if let Some(&pending) = self.keyed.get(&key) {
+            match self.key_policy {
+                KeyPolicy::Ignore => return pending,
+                KeyPolicy::Replace => {
+                    self.cancel(pending);
+                }
+            }
+        }
+        self.schedule_keyed(key, after, Box::new(timer))
+    }
+
+    /// Schedule a timer to occur after the given number of ticks have elapsed, cancelling
+    /// any timer still pending for the same key regardless of the [KeyPolicy]. Calling this
+    /// repeatedly means only the latest timer fires, once activity has settled.
+    pub fn debounce<S>(&mut self, key: u64, after: usize, timer: S) -> TimerHandle
+    where
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        if let Some(&pending) = self.keyed.get(&key) {
+            self.cancel(pending);
+        }
+        self.schedule_keyed(key, after, Box::new(timer))
+    }
+
+    /// Schedule a timer like [Timers::debounce], treating the key as the name of a slot that
+    /// holds at most one pending timer. Returns the handle of the timer that was cancelled
+    /// to make room, if any, along with the handle of the new timer, so that callers can
+    /// tell when a previous timer was superseded.
+    pub fn replace_keyed<S>(
+        &mut self,
+        key: u64,
+        after: usize,
+        timer: S,
+    ) -> (Option<TimerHandle>, TimerHandle)
+    where
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        let replaced = self
+            .keyed
+            .get(&key)
+            .copied()
+            .filter(|&pending| self.cancel(pending));
+        (replaced, self.schedule_keyed(key, after, Box::new(timer)))
+    }
+
+    /// Cancel the pending timer for the key if there is one, and otherwise schedule the timer
+    /// under the key, e.g. to start a timed effect on one key press and cancel it on the
+    /// next. Returns the handle of the new timer, or `None` if a timer was cancelled instead.
+    pub fn toggle<S>(&mut self, key: u64, after: usize, timer: S) -> Option<TimerHandle>
+    where
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        match self.keyed.get(&key).copied() {
+            Some(pending) if self.cancel(pending) => None,
+            _ => Some(self.schedule_keyed(key, after, Box::new(timer))),
+        }
+    }
+
+    /// Schedule a timer to occur on the next tick, unless a timer for the same key fired less
+    /// than its `min_interval` ticks ago, in which case it occurs as soon as those ticks
+    /// have passed. If a timer is still pending for the key, the new timer is dropped and the
+    /// handle of the pending timer is returned. Like [Timers::now], a timer that is not held
+    /// back is queued for the next drain of the timer stage rather than run in place.
+    ///
+    /// Throttle keys are separate from the keys of [Timers::after_keyed], [Timers::debounce],
+    /// [Timers::replace_keyed] and [Timers::toggle], so the same key can be used for both.
+    pub fn throttle<S>(&mut self, key: u64, min_interval: usize, timer: S) -> TimerHandle
+    where
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        if let Some(&pending) = self.throttle_pending.get(&key) {
+            return pending;
+        }
+        let now = self.wheel.elapsed;
+        while let Some(&(expires, expired)) = self.throttle_expiry.first() {
+            if expires > now {
+                break;
+            }
+            self.throttle_expiry.pop_first();
+            self.throttled.remove(&expired);
+        }
+        let after = self
+            .throttled
+            .get(&key)
+            .map_or(0, |until| until.saturating_sub(now) as usize);
+        let system = Box::new(move |world: &mut World| {
+            get_timers_mut(world).throttle_fired(key, min_interval);
+            timer(world);
+        });
+        let timer = Timer {
+            throttle_key: Some(key),
+            ..self.new_timer(TimerSystem::Once(system))
+        };
+        let handle = self.schedule(after, timer);
+        if self.targets.contains_key(&handle) {
+            self.throttle_pending.insert(key, handle);
+        }
+        handle
+    }
+
+    /// Set what [Timers::after_keyed] does when a timer is already pending for its key.
+    pub fn set_key_policy(&mut self, policy: KeyPolicy) {
+        self.key_policy = policy;
+    }
+
+    /// Schedule a sequence of timers, one for each delay. Each delay is measured from now,
+    /// not from the previous timer in the sequence, and the factory is passed the index of
+    /// the step it is creating a timer for.
+    pub fn schedule_sequence<I, F>(&mut self, delays: I, mut timer_factory: F) -> Vec<TimerHandle>
+    where
+        I: IntoIterator<Item = usize>,
+        F: FnMut(usize) -> BoxedSystem,
+    {
+        delays
+            .into_iter()
+            .enumerate()
+            .map(|(step, after)| {
+                let timer = self.new_timer(TimerSystem::Once(timer_factory(step)));
+                self.schedule(after, timer)
+            })
+            .collect()
+    }
+
+    /// Schedule a timer to occur every `interval` ticks until it is cancelled. The first
+    /// occurrence is after `interval` ticks have elapsed.
+    pub fn every<S>(&mut self, interval: usize, timer: S) -> TimerHandle
+    where
+        S: FnMut(&mut World) + MaybeSendSync + 'static,
+    {
+        assert!(
+            interval > 0,
+            "repeating timer interval must be at least one tick"
+        );
+        let timer = self.new_timer(TimerSystem::Repeating {
+            system: Box::new(timer),
+            interval,
+        });
+        self.schedule(interval, timer)
+    }
+
+    /// Schedule a timer to occur on the next tick and then every `interval` ticks until it
+    /// is cancelled. Unlike [Timers::every], the first occurrence does not wait a full
+    /// interval. The returned handle covers every occurrence.
+    pub fn start_now_then_every<S>(&mut self, interval: usize, timer: S) -> TimerHandle
+    where
+        S: FnMut(&mut World) + MaybeSendSync + 'static,
+    {
+        assert!(
+            interval > 0,
+            "repeating timer interval must be at least one tick"
+        );
+        let timer = self.new_timer(TimerSystem::Repeating {
+            system: Box::new(timer),
+            interval,
+        });
+        self.schedule(0, timer)
+    }
+
+    /// Toggle the presence of a component on an entity every `interval` ticks, `count` times,
+    /// e.g. to flash an entity while it is invulnerable. The first toggle is after `interval`
+    /// ticks have elapsed. Blinking stops early if the entity is despawned.
+    pub fn blink<C>(&mut self, entity: Entity, interval: usize, count: usize)
+    where
+        C: Component + Default,
+    {
+        assert!(interval > 0, "blink interval must be at least one tick");
+        if count > 0 {
+            self.after(interval, move |world: &mut World| {
+                blink::<C>(world, entity, interval, count)
+            });
+        }
+    }
+
+    /// Advance a sprite animation on an entity by one frame every `frame_ticks` ticks, over
+    /// `frames` frames. The first advance, from frame 0 to frame 1, is after `frame_ticks`
+    /// ticks have elapsed. The frame index is written to the entity's component `C` with
+    /// `set_index`, e.g. `|sprite: &mut TextureAtlasSprite, index| sprite.index = index`.
+    ///
+    /// A looping animation wraps back around to frame 0 after the last frame and runs until
+    /// the entity is despawned or loses the component, which also stops a non-looping
+    /// animation early.
+    pub fn animate<C, F>(
+        &mut self,
+        entity: Entity,
+        frame_ticks: usize,
+        frames: usize,
+        looping: bool,
+        set_index: F,
+    ) where
+        C: Component,
+        F: FnMut(&mut C, usize) + MaybeSendSync + 'static,
+    {
+        assert!(
+            frame_ticks > 0,
+            "animation frames must last at least one tick"
+        );
+        if frames > 1 {
+            self.after(frame_ticks, move |world: &mut World| {
+                animate::<C, F>(world, entity, frame_ticks, frames, looping, 1, set_index)
+            });
+        }
+    }
+
+    /// Count down from `from` to one, calling `on_tick` with the remaining count every `step`
+    /// ticks, starting on the next tick, and then call `on_done` `step` ticks after the
+    /// last count, e.g. for a "3... 2... 1... go!" countdown.
+    pub fn count_down<T, D>(&mut self, from: usize, step: usize, on_tick: T, on_done: D)
+    where
+        T: FnMut(&mut World, usize) + MaybeSendSync + 'static,
+        D: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        assert!(step > 0, "count down step must be at least one tick");
+        self.after(0, move |world: &mut World| {
+            count_down(world, from, step, on_tick, on_done)
+        });
+    }
+
+    /// Drive a value over the next `duration` ticks, calling `setter` once per tick, starting
+    /// on the next tick, with the progress so far: `1 / duration` on the first tick, up to
+    /// exactly `1.0` on the last, e.g. to fade a color or slide an entity. With a `duration`
+    /// of zero, `setter` is called once with `1.0` on the next tick.
+    pub fn tween<F>(&mut self, duration: usize, setter: F)
+    where
+        F: FnMut(&mut World, f32) + MaybeSendSync + 'static,
+    {
+        self.after(0, move |world: &mut World| {
+            tween(world, 1, duration, setter)
+        });
+    }
+
+    /// Set the maximum number of times a repeating timer may fire, or `None` for no limit.
+    /// A repeating timer that reaches the limit is cancelled with a warning. This is a safety
+    /// net against runaway repeaters on long-running servers.
+    pub fn set_max_repeats(&mut self, max: Option<u64>) {
+        self.max_repeats = max;
+    }
+
+    /// Enable catching up on missed ticks at the given number of ticks per second, or
+    /// disable it with `None`.
+    ///
+    /// Normally [TimerPlugin] advances the clock by exactly one tick each update. When
+    /// catching up is enabled it compares the real time elapsed, as reported by Bevy's
+    /// [Time] resource, against the tick rate, and runs as many ticks as it has fallen
+    /// behind by, so that timers do not stall when updates are throttled (for example while
+    /// the window is minimized). At least one tick still runs every update.
+    ///
+    /// This makes the number of ticks per update depend on wall-clock time, so it gives up
+    /// the determinism of purely tick based timers. After a long stall, such as an hour
+    /// spent minimized, catching up can take so long that the next update falls behind
+    /// too; use [Timers::set_max_catch_up] to limit the ticks run in one update.
+    pub fn set_catch_up(&mut self, ticks_per_second: Option<f64>) {
+        self.catch_up_rate = ticks_per_second;
+    }
+
+    /// Set the most ticks that [TimerPlugin] runs in one update when catching up on missed
+    /// ticks, or `None` for no limit. Any ticks owed beyond the limit are skipped rather
+    /// than carried over to the next update. Defaults to no limit.
+    pub fn set_max_catch_up(&mut self, max_ticks: Option<usize>) {
+        self.max_catch_up = max_ticks;
+    }
+
+    /// Set the maximum number of timers run on each tick, or `None` for no limit. When more
+    /// timers fire than the budget allows, the timers that run are picked by [Phase] and then
+    /// in the order they were scheduled, and the rest are moved to the next tick, where they
+    /// compete with the timers of that tick in the same way. This keeps critical timers in
+    /// [Phase::Early] from being starved by a flood of cosmetic ones in later phases.
+    pub fn set_tick_budget(&mut self, max: Option<usize>) {
+        self.tick_budget = max;
+    }
+
+    /// Set what the clock does while the primary window is not focused. Defaults to
+    /// [UnfocusedPolicy::Ignore].
+    pub fn set_unfocused_policy(&mut self, policy: UnfocusedPolicy) {
+        self.unfocused_policy = policy;
+    }
+
+    /// Return true if the clock advances on updates, i.e. it is neither paused nor stopped by
+    /// [UnfocusedPolicy::Pause] while the window is unfocused.
+    pub fn is_clock_running(&self) -> bool {
+        !self.paused && (self.focused || self.unfocused_policy != UnfocusedPolicy::Pause)
+    }
+
+    /// Return a handle that can schedule timers from other threads.
+    pub fn sender(&self) -> TimerSender {
+        TimerSender {
+            sender: self.sender.clone(),
+        }
+    }
+
+    /// Register a callback to run every time the current tick of the given level wraps
+    /// back around to zero, i.e. every 64 ticks for level 0, every 4096 ticks for level 1,
+    /// and so on. Callbacks run after the timers of the tick on which the wrap occurs.
+    pub fn on_level_wrap<F>(&mut self, level: usize, cb: F)
+    where
+        F: FnMut(&mut World) + MaybeSendSync + 'static,
+    {
+        assert!(level < LEVELS, "timer wheel only has {} levels", LEVELS);
+        self.wrap_hooks.push((level, Box::new(cb)));
+    }
+
+    /// Register a callback to run every time a cascade moves a pending timer down a level of
+    /// the timing wheel, given the handle of the timer, the level it moved from and to, and
+    /// the number of ticks it has left, counted like the delay passed to [Timers::after].
+    /// This replaces any callback registered before, and is meant for debugging long timers
+    /// that fire on the wrong tick. Timers of [Timers::after_realtime] are not reported.
+    pub fn on_migrate<F>(&mut self, mut cb: F)
+    where
+        F: FnMut(TimerHandle, usize, usize, usize) + MaybeSendSync + 'static,
+    {
+        self.wheel.on_migrate = Some(Box::new(move |timer: &Timer, from, to, remaining| {
+            cb(timer.handle, from, to, remaining)
+        }));
+    }
+
+    /// Register a callback to run once, at the start of the first tick of the timer clock
+    /// and before any timers fire. If the clock has already started, the callback runs at
+    /// the start of the next tick instead.
+    pub fn on_first_tick<F>(&mut self, cb: F)
+    where
+        F: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        self.first_tick_hooks.push(Box::new(cb));
+    }
+
+    /// Cancel a pending timer. Returns false if the timer has already run or been
+    /// cancelled.
+    ///
+    /// A timer may cancel another timer that fires on the same tick but has yet to run, in
+    /// which case the cancelled timer is skipped.
+    pub fn cancel(&mut self, handle: TimerHandle) -> bool {
+        self.record(RecordedCall::Cancel { handle });
+        match self.remove(handle) {
+            Some(timer) => {
+                self.forget(&timer, false);
+                true
+            }
+            None if self.in_flight.contains(&handle) => self.skipped.insert(handle),
+            None => false,
+        }
+    }
+
+    /// Cancel a pending timer and return its closure instead of dropping it, so that it can
+    /// be rescheduled or run elsewhere. A repeating timer's closure is returned to be run
+    /// once. Returns `None` if the timer has already run or been cancelled.
+    pub fn take(&mut self, handle: TimerHandle) -> Option<BoxedSystem> {
+        let timer = self.remove(handle)?;
+        self.forget(&timer, false);
+        Some(match timer.system {
+            TimerSystem::Once(system) => system,
+            TimerSystem::ReadOnly(system) => {
+                Box::new(move |world: &mut World| run_read_only(world, &mut vec![system]))
+            }
+            TimerSystem::Repeating { mut system, .. } => {
+                Box::new(move |world: &mut World| system(world))
+            }
+        })
+    }
+
+    /// Cancel every pending repeating timer, leaving one-shot timers in place. Returns the
+    /// number of timers cancelled.
+    pub fn cancel_all_repeating(&mut self) -> usize {
+        self.cancel_matching(|timer| matches!(timer.system, TimerSystem::Repeating { .. }))
+    }
+
+    /// Run a pending timer immediately instead of waiting for it to fire. A repeating timer
+    /// next fires a full interval after this. Returns false if the timer has already fired
+    /// or been cancelled.
+    ///
+    /// This takes the [World] rather than the resource so that the timer can be given
+    /// access to it. It may be called from within another timer.
+    pub fn fire_now(world: &mut World, handle: TimerHandle) -> bool {
+        let mut timers = get_timers_mut(world);
+        let timer = match timers.remove(handle) {
+            Some(timer) => timer,
+            None => return false,
+        };
+        if !matches!(timer.system, TimerSystem::Repeating { .. }) {
+            timers.forget(&timer, true);
+        }
+        timers.fires += 1;
+        let now = timers.clock(timer.realtime).elapsed;
+        run_timer(world, now, timer);
+        true
+    }
+
+    /// Advance the clock by one tick and run at most `max` of the timers that fire, for
+    /// callers driving timers from their own loop. One-shot timers over the budget are
+    /// returned for the caller to run, while repeating timers over the budget are moved to
+    /// the next tick. Timers cancelled before they could run do not count against the
+    /// budget. Level wrap callbacks are not run.
+    pub fn drain_budgeted(world: &mut World, max: Option<usize>) -> Vec<BoxedSystem> {
+        let timers = get_timers_mut(world).tick();
+        let budget = max.unwrap_or(usize::MAX);
+        let mut ran = 0;
+        let mut deferred = Vec::new();
+        for (target, timer) in timers {
+            let repeating = matches!(timer.system, TimerSystem::Repeating { .. });
+            if ran >= budget && repeating {
+                get_timers_mut(world).defer(timer);
+                continue;
+            }
+            if !get_timers_mut(world).start(target, &timer) {
+                continue;
+            }
+            if ran < budget {
+                ran += 1;
+                run_timer(world, target, timer);
+                continue;
+            }
+            match timer.system {
+                TimerSystem::Once(system) => deferred.push(system),
+                TimerSystem::ReadOnly(system) => {
+                    deferred.push(Box::new(move |world: &mut World| {
+                        run_read_only(world, &mut vec![system])
+                    }))
+                }
+                TimerSystem::Repeating { .. } => unreachable!(),
+            }
+        }
+        get_timers_mut(world).draining = false;
+        deferred
+    }
+
+    /// Advance the clock by one tick and return the timers that fire, in the order they would
+    /// run, without running them, so that callers driving timers from their own loop can
+    /// filter, reorder or instrument the batch. This is what the timer stage runs on every
+    /// tick. Read-only timers that fire next to each other are returned as one closure, so
+    /// that they can run in parallel with the `rayon` feature.
+    ///
+    /// Each timer is only taken out of the batch when its closure runs, so a timer cancelled
+    /// by an earlier closure is skipped, and the budget set with [Timers::set_tick_budget]
+    /// is applied in the order the closures are run. A repeating timer reschedules itself
+    /// when its closure runs. Timers whose closures are dropped without being run are
+    /// cancelled when the next tick starts. Level wrap callbacks and the timers of
+    /// [Timers::after_realtime] are not run.
+    pub fn advance_one(&mut self) -> Vec<BoxedSystem> {
+        let due = self.advance();
+        self.draining = false;
+        due
+    }
+
+    /// Schedule a timer to occur right now.
+    ///
+    /// The timer is queued for the next drain of the timer stage rather than run in place,
+    /// so if that stage has already run this frame the timer runs on the next one. Use
+    /// [Timers::now_immediate] to run a timer synchronously instead.
+    pub fn now<S>(&mut self, timer: S) -> TimerHandle
+    where
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        self.after(0, timer)
+    }
+
+    /// Schedule a timer that is not `Send` or `Sync`, such as a timer that accesses a
+    /// [NonSend] resource, to occur after the given number of ticks have elapsed. Otherwise
+    /// the timer behaves like one scheduled with [Timers::after], and can be cancelled with
+    /// the returned handle.
+    ///
+    /// The closure is kept in a non-send resource rather than in [Timers], so this takes the
+    /// [World]. Timers are only run on the main thread, which makes this safe, but non-send
+    /// timers will not be usable with any drain that runs timers off the main thread. The
+    /// closure of a timer that is cancelled is dropped on the next tick.
+    pub fn after_nonsend<S>(world: &mut World, after: usize, timer: S) -> TimerHandle
+    where
+        S: FnOnce(&mut World) + 'static,
+    {
+        drop_cancelled_nonsend(world);
+        let dropped = get_nonsend_timers_mut(world).dropped.clone();
+        let mut timers = get_timers_mut(world);
+        let mut placeholder = timers.new_timer(TimerSystem::Once(Box::new(|_: &mut World| {})));
+        let guard = NonSendGuard {
+            handle: placeholder.handle,
+            dropped,
+        };
+        placeholder.system = TimerSystem::Once(Box::new(move |world: &mut World| guard.run(world)));
+        let handle = timers.schedule(after, placeholder);
+        if timers.is_pending(handle) {
+            get_nonsend_timers_mut(world)
+                .timers
+                .insert(handle, Box::new(timer));
+        }
+        handle
+    }
+
+    /// Run a timer synchronously, before this function returns, rather than queueing it
+    /// like [Timers::now]. The timer still counts towards the scheduled and fired totals.
+    pub fn now_immediate<S>(world: &mut World, timer: S)
+    where
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        let mut timers = get_timers_mut(world);
+        timers.total_scheduled += 1;
+        timers.total_fired += 1;
+        timers.fires += 1;
+        timer(world);
+    }
+
+    /// Set whether [Timers::after_in] runs timers scheduled after zero ticks synchronously
+    /// when called outside of the timer stage. Disabled by default.
+    ///
+    /// Normally a timer scheduled after zero ticks fires on the next tick, which keeps the
+    /// order in which timers run purely tick based, but can leave the timer running a frame
+    /// late if the timer stage has already run this frame. Immediate mode avoids that delay
+    /// at the cost of running the timer in the middle of whatever scheduled it. Timers
+    /// scheduled while the timers of a tick are being run always fire on the next tick, so
+    /// that a timer cannot keep the current tick running forever.
+    pub fn set_immediate_mode(&mut self, enabled: bool) {
+        self.immediate_mode = enabled;
+    }
+
+    /// Schedule a timer like [Timers::after], except that with immediate mode enabled by
+    /// [Timers::set_immediate_mode] a timer scheduled after zero ticks from outside of the
+    /// timer stage runs before this function returns.
+    pub fn after_in<D, S>(world: &mut World, after: D, timer: S) -> TimerHandle
+    where
+        D: IntoTicks,
+        S: FnOnce(&mut World) + MaybeSendSync + 'static,
+    {
+        let mut timers = get_timers_mut(world);
+        let after = after.into_ticks(timers.tick_rate);
+        if after > 0 || !timers.immediate_mode || timers.draining {
+            return timers.after(after, timer);
+        }
+        let handle = timers.new_handle();
+        Timers::now_immediate(world, timer);
+        handle
+    }
+
+    /// Change the tag of a pending timer without rescheduling it. Returns false if the timer
+    /// has already fired or been cancelled.
+    pub fn retag(&mut self, handle: TimerHandle, new_tag: Option<u64>) -> bool {
+        match self.get_mut(handle) {
+            Some(timer) => {
+                timer.tag = new_tag;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Change the interval of a pending repeating timer. The occurrence that is already
+    /// pending stays where it is, and the new interval is used from the next reschedule on.
+    /// Returns false if the timer is not a pending repeating timer.
+    pub fn set_interval(&mut self, handle: TimerHandle, new_interval: usize) -> bool {
+        assert!(
+            new_interval > 0,
+            "repeating timer interval must be at least one tick"
+        );
+        match self.get_mut(handle).map(|timer| &mut timer.system) {
+            Some(TimerSystem::Repeating { interval, .. }) => {
+                *interval = new_interval;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Cancel every pending timer that carries the given tag. Returns the number of timers
+    /// cancelled.
+    pub fn cancel_tag(&mut self, tag: u64) -> usize {
+        self.cancel_matching(|timer| timer.tag == Some(tag))
+    }
+
+    /// Pause every pending timer that carries the given tag, e.g. all of an entity's timers
+    /// while it is stunned. Paused timers keep the number of ticks they have left, and only
+    /// count down again once [Timers::resume_group] is called. They can still be cancelled,
+    /// but are not listed by the methods that inspect the schedule until they are resumed.
+    /// Returns the number of timers paused.
+    pub fn pause_group(&mut self, tag: u64) -> usize {
+        let elapsed = self.wheel.elapsed;
+        let realtime_elapsed = self.realtime.elapsed;
+        let mut paused: Vec<_> = self
+            .wheel
+            .remove_all(|timer| timer.tag == Some(tag))
+            .into_iter()
+            .map(|(target, timer)| ((target - elapsed) as usize, timer))
+            .collect();
+        paused.extend(
+            self.realtime
+                .remove_all(|timer| timer.tag == Some(tag))
+                .into_iter()
+                .map(|(target, timer)| ((target - realtime_elapsed) as usize, timer)),
+        );
+        for (_, timer) in &paused {
+            self.targets.remove(&timer.handle);
+        }
+        let count = paused.len();
+        if count > 0 {
+            self.frozen.entry(tag).or_default().extend(paused);
+        }
+        count
+    }
+
+    /// Resume the timers paused by [Timers::pause_group] for the given tag, each firing once
+    /// the ticks it had left when it was paused have passed. Returns the number of timers
+    /// resumed.
+    pub fn resume_group(&mut self, tag: u64) -> usize {
+        let paused = self.frozen.remove(&tag).unwrap_or_default();
+        let count = paused.len();
+        for (remaining, timer) in paused {
+            let handle = timer.handle;
+            let clock = self.clock_mut(timer.realtime);
+            let target = clock.elapsed + remaining as u64;
+            clock.insert(target, timer);
+            self.targets.insert(handle, target);
+        }
+        count
+    }
+
+    /// Cancel every pending timer for which the predicate returns true, given the handle of
+    /// the timer, the absolute tick on which it fires, and its tag. Returns the number of
+    /// timers cancelled.
+    pub fn cancel_where<F>(&mut self, mut predicate: F) -> usize
+    where
+        F: FnMut(TimerHandle, u64, Option<u64>) -> bool,
+    {
+        let handles: Vec<_> = self
+            .wheel
+            .iter()
+            .chain(self.realtime.iter())
+            .filter(|(target, timer)| predicate(timer.handle, *target, timer.tag))
+            .map(|(_, timer)| timer.handle)
+            .collect();
+        handles
+            .into_iter()
+            .filter(|&handle| self.cancel(handle))
+            .count()
+    }
+
+    /// Return the number of pending timers that carry the given tag.
+    pub fn count_tag(&self, tag: u64) -> usize {
+        self.wheel
+            .iter()
+            .chain(self.realtime.iter())
+            .filter(|(_, timer)| timer.tag == Some(tag))
+            .count()
+    }
+
+    /// Return the handles of every pending tagged timer, grouped by tag. Untagged timers are
+    /// left out, and the handles of each tag are in the order they were scheduled.
+    pub fn pending_by_tag(&self) -> BTreeMap<u64, Vec<TimerHandle>> {
+        let mut groups: BTreeMap<u64, Vec<TimerHandle>> = BTreeMap::new();
+        for (_, timer) in self.wheel.iter().chain(self.realtime.iter()) {
+            if let Some(tag) = timer.tag {
+                groups.entry(tag).or_default().push(timer.handle);
+            }
+        }
+        for handles in groups.values_mut() {
+            handles.sort();
+        }
+        groups
+    }
+
+    /// Return true if any timer will fire on the next tick. This only inspects the slot of
+    /// the next tick, and the higher level slots that cascade into it when a level wraps
+    /// around, so it is cheap enough to call every update.
+    ///
+    /// Both clocks are checked, but the answer is about the next tick of each clock rather
+    /// than the next update: with a time scale below one, the next real tick may not run a
+    /// tick of the scaled clock at all, and with a time scale above one, timers firing on
+    /// the later scaled ticks of the same update are not accounted for. Timers sent through
+    /// a [TimerSender] are not known until the next tick starts and so are not accounted for
+    /// either.
+    pub fn fires_next_tick(&self) -> bool {
+        self.wheel.fires_next() || self.realtime.fires_next()
+    }
+
+    /// Return how many timers fire on each of the next `horizon` ticks, where index `0` is
+    /// the next tick. Every timer knows the exact tick it fires on, so timers that have yet
+    /// to cascade down the wheel are counted too. Repeating timers are only counted for
+    /// their next firing.
+    pub fn snapshot_counts(&self, horizon: usize) -> Vec<usize> {
+        let mut counts = vec![0; horizon];
+        let elapsed = self.wheel.elapsed;
+        self.for_each_pending(|_, target, _| {
+            if let Some(count) = counts.get_mut((target - elapsed) as usize) {
+                *count += 1;
+            }
+        });
+        counts
+    }
+
+    /// Return the handles and absolute target ticks of the pending timers that fire between
+    /// `from_ticks` (inclusive) and `to_ticks` (exclusive) ticks from now, where `0` is the
+    /// next tick. The timers are returned in the order they fire.
+    pub fn scheduled_between(&self, from_ticks: usize, to_ticks: usize) -> Vec<(TimerHandle, u64)> {
+        let elapsed = self.wheel.elapsed;
+        let window = elapsed + from_ticks as u64..elapsed + to_ticks as u64;
+        let mut timers = Vec::new();
+        self.for_each_pending(|handle, target, _| {
+            if window.contains(&target) {
+                timers.push((handle, target));
+            }
+        });
+        timers.sort_by_key(|&(handle, target)| (target, handle));
+        timers
+    }
+
+    /// Return the handles and absolute target ticks of every pending timer, sorted by target
+    /// tick and then by the order they were scheduled in, e.g. to list upcoming timers in a
+    /// debug panel. This is the sorted counterpart of [Timers::for_each_pending].
+    pub fn pending_sorted(&self) -> Vec<(TimerHandle, u64)> {
+        let mut timers = Vec::new();
+        self.for_each_pending(|handle, target, _| timers.push((handle, target)));
+        timers.sort_by_key(|&(handle, target)| (target, handle));
+        timers
+    }
+
+    /// Visit every pending timer with its handle, the absolute tick on which it fires, and
+    /// its tag. The timers are visited in no particular order.
+    pub fn for_each_pending<F>(&self, mut f: F)
+    where
+        F: FnMut(TimerHandle, u64, Option<u64>),
+    {
+        for (target, timer) in self.wheel.iter() {
+            f(timer.handle, target, timer.tag);
+        }
+    }
+
+    /// Replace the closure of every pending one-shot timer, including those paused by
+    /// [Timers::pause_group], with the result of the adapter, e.g. to wrap each timer with
+    /// logging in a debug build. The handle, firing tick and tag of each timer are left
+    /// unchanged. Repeating and read-only timers are not wrapped.
+    pub fn wrap_pending<F>(&mut self, f: F)
+    where
+        F: Fn(BoxedSystem) -> BoxedSystem,
+    {
+        let frozen = self.frozen.values_mut().flatten().map(|(_, timer)| timer);
+        for timer in self
+            .wheel
+            .iter_mut()
+            .chain(self.realtime.iter_mut())
+            .chain(frozen)
+        {
+            if let TimerSystem::Once(system) = &mut timer.system {
+                let unwrapped = mem::replace(system, Box::new(|_: &mut World| {}));
+                *system = f(unwrapped);
+            }
+        }
+    }
+
+    /// Run the wheel's scheduling and cascading logic without any closures or a [World].
+    ///
+    /// Each `(delay, id)` pair is scheduled as if by `after(delay, ..)` on a fresh [Timers],
+    /// and the wheel is then ticked `total_ticks` times. Returns the `(tick, id)` pairs of
+    /// every timer that fired, in firing order, where `tick` counts from zero for the first
+    /// tick. A timer scheduled after `delay` ticks should therefore fire on tick `delay`.
+    pub fn simulate(schedule: &[(usize, u64)], total_ticks: usize) -> Vec<(u64, u64)> {
+        let mut wheel = TimingWheelHierarchy::default();
+        for &(delay, id) in schedule {
+            wheel.schedule(delay, id);
+        }
+        let mut fired = Vec::new();
+        for _ in 0..total_ticks {
+            fired.extend(wheel.tick());
+        }
+        fired
+    }
+
+    /// Return the number of timers that are pending.
+    pub fn len(&self) -> usize {
+        self.pending
+    }
+
+    /// Return the number of timers that are pending, which is what the limit set with
+    /// [Timers::set_max_pending] is checked against. This is the same as [Timers::len].
+    pub fn pending_count(&self) -> usize {
+        self.pending
+    }
+
+    /// Return true if no timers are pending.
+    pub fn is_empty(&self) -> bool {
+        self.pending == 0
+    }
+
+    /// Cancel every repeating timer, then tick the clock until every remaining timer has
+    /// fired, e.g. to finalize a headless simulation deterministically. Timers scheduled by
+    /// the timers being run are run as well, so this never returns if timers keep on
+    /// scheduling more timers. The timers are flushed even if they are paused, and groups
+    /// paused with [Timers::pause_group] are resumed. Each clock only ticks while it has
+    /// timers pending, regardless of the time scale, so the timers of
+    /// [Timers::after_realtime] are flushed on their own clock.
+    pub fn flush(world: &mut World) {
+        let mut timers = get_timers_mut(world);
+        let tags: Vec<_> = timers.frozen.keys().copied().collect();
+        for tag in tags {
+            timers.resume_group(tag);
+        }
+        timers.cancel_all_repeating();
+        timers.receive();
+        loop {
+            let timers = get_timers(world);
+            let scaled = !timers.wheel.is_empty();
+            let realtime = !timers.realtime.is_empty();
+            if !scaled && !realtime {
+                break;
+            }
+            if realtime {
+                let due = get_timers_mut(world).advance_realtime();
+                run_batch(world, due);
+            }
+            if scaled {
+                run_tick(world);
+            }
+        }
+    }
+
+    /// Tick the clocks up to and including the next tick on which any timer fires, for
+    /// event-driven simulation loops. Returns the number of ticks run, or `None` if no
+    /// timers are pending. This runs even if the timers are paused.
+    ///
+    /// Both the scaled and the unscaled clock advance one tick at a time, ignoring the time
+    /// scale, so timers scheduled with [Timers::after_realtime] are found as well.
+    pub fn run_to_next(world: &mut World) -> Option<usize> {
+        let mut timers = get_timers_mut(world);
+        timers.receive();
+        let until = |wheel: &TimingWheelHierarchy<Timer>| {
+            let next = wheel.iter().map(|(target, _)| target).min()?;
+            Some(next.saturating_sub(wheel.elapsed))
+        };
+        let ticks = match (until(&timers.wheel), until(&timers.realtime)) {
+            (Some(scaled), Some(realtime)) => scaled.min(realtime),
+            (scaled, realtime) => scaled.or(realtime)?,
+        } as usize
+            + 1;
+        for _ in 0..ticks {
+            let due = get_timers_mut(world).advance_realtime();
+            run_batch(world, due);
+            run_tick(world);
+        }
+        Some(ticks)
+    }
+
+    /// Return the total number of timers that have ever been scheduled.
+    pub fn total_scheduled(&self) -> u64 {
+        self.total_scheduled
+    }
+
+    /// Return the total number of timers that have finished by firing. Repeating timers are
+    /// never counted here, as they only finish when they are cancelled.
+    pub fn total_fired(&self) -> u64 {
+        self.total_fired
+    }
+
+    /// Return the number of times any timer has run, counting every run of a repeating timer.
+    pub fn fires(&self) -> u64 {
+        self.fires
+    }
+
+    /// Return the number of higher level slots that have been cascaded down to the lower
+    /// levels, whether or not they held any timers. The wheels of the scaled clock and of
+    /// [Timers::after_realtime] both count.
+    pub fn cascades(&self) -> u64 {
+        self.wheel.cascades + self.realtime.cascades
+    }
+
+    /// Return the number of timers that have been moved down a level by a cascade. A timer
+    /// may be moved more than once before it fires.
+    pub fn migrations(&self) -> u64 {
+        self.wheel.migrations + self.realtime.migrations
+    }
+
+    /// Return the total number of timers that have been cancelled or extracted before they
+    /// could finish, including repeating timers stopped by [Timers::set_max_repeats].
+    ///
+    /// The number of pending timers always equals `total_scheduled - total_fired -
+    /// total_cancelled`, which can be used to check for leaks.
+    pub fn total_cancelled(&self) -> u64 {
+        self.total_cancelled
+    }
+
+    /// Remove every pending timer, leaving this resource empty.
+    pub fn extract(&mut self) -> PendingTimers {
+        let elapsed = self.wheel.elapsed;
+        let realtime_elapsed = self.realtime.elapsed;
+        let mut timers: Vec<_> = self
+            .wheel
+            .drain()
+            .into_iter()
+            .map(|(target, timer)| (target - elapsed, timer))
+            .chain(
+                self.realtime
+                    .drain()
+                    .into_iter()
+                    .map(|(target, timer)| (target - realtime_elapsed, timer)),
+            )
+            .chain(
+                mem::take(&mut self.frozen)
+                    .into_values()
+                    .flatten()
+                    .map(|(remaining, timer)| (remaining as u64, timer)),
+            )
+            .map(|(remaining, timer)| PendingTimer {
+                handle: timer.handle,
+                remaining: remaining as usize,
+                phase: timer.phase,
+                tag: timer.tag,
+                key: timer.key,
+                throttle_key: timer.throttle_key,
+                label: timer.label,
+                named: timer.named.map(|named| *named),
+                repeats: timer.repeats,
+                realtime: timer.realtime,
+                system: timer.system,
+            })
+            .collect();
+        timers.sort_by_key(|timer| (timer.remaining, timer.phase, timer.handle));
+        self.keyed.clear();
+        self.throttle_pending.clear();
+        self.targets.clear();
+        self.total_cancelled += self.pending as u64;
+        self.pending = 0;
+        PendingTimers(timers)
+    }
+
+    /// Return true if both resources have the same timers pending, for tests that check two
+    /// independently built schedules, or a round trip through [Timers::extract] and
+    /// [Timers::from_pending], match.
+    ///
+    /// Timers are compared by the number of ticks left until they fire, their clock, phase,
+    /// tag and kind, including the interval of repeating timers. Closures cannot be compared,
+    /// and handles, the absolute tick of the clock and the level of the wheel a timer sits
+    /// on do not affect when a timer fires, so they are ignored.
+    pub fn schedule_eq(&self, other: &Self) -> bool {
+        self.schedule_summary() == other.schedule_summary()
+    }
+
+    /// Describe every pending timer for [Timers::schedule_eq], in a canonical order.
+    #[allow(clippy::type_complexity)]
+    fn schedule_summary(&self) -> Vec<(u64, bool, Phase, Option<u64>, Option<usize>, bool)> {
+        let mut summary: Vec<_> = [&self.wheel, &self.realtime]
+            .into_iter()
+            .flat_map(|wheel| {
+                wheel.iter().map(move |(target, timer)| {
+                    let (interval, read_only) = match timer.system {
+                        TimerSystem::Once(_) => (None, false),
+                        TimerSystem::ReadOnly(_) => (None, true),
+                        TimerSystem::Repeating { interval, .. } => (Some(interval), false),
+                    };
+                    (
+                        target - wheel.elapsed,
+                        timer.realtime,
+                        timer.phase,
+                        timer.tag,
+                        interval,
+                        read_only,
+                    )
+                })
+            })
+            .collect();
+        summary.sort();
+        summary
+    }
+
+    /// Return a new [Timers] resource with the same named timers pending, scheduled with
+    /// [Timers::after_named], on the same ticks, e.g. to fork a simulation for lookahead.
+    /// Timers scheduled with closures cannot be copied and are left out of the copy; their
+    /// number is returned alongside it. The clocks of the copy start on the same ticks, but
+    /// all of its settings are the defaults.
+    pub fn duplicate_schedule(&self) -> (Timers, usize) {
+        let mut copy = Timers::default();
+        copy.wheel.elapsed = self.wheel.elapsed;
+        copy.realtime.elapsed = self.realtime.elapsed;
+        let mut dropped = 0;
+        for wheel in [&self.wheel, &self.realtime] {
+            let mut timers: Vec<_> = wheel.iter().collect();
+            timers.sort_by_key(|(_, timer)| timer.handle);
+            for (target, timer) in timers {
+                let (name, handler, params) = match timer.named.as_deref() {
+                    Some((name, handler, params)) => (name, *handler, params.clone()),
+                    None => {
+                        dropped += 1;
+                        continue;
+                    }
+                };
+                let duplicate = Timer {
+                    phase: timer.phase,
+                    tag: timer.tag,
+                    realtime: timer.realtime,
+                    ..copy.named_timer(name, handler, params)
+                };
+                copy.schedule((target - wheel.elapsed) as usize, duplicate);
+            }
+        }
+        (copy, dropped)
+    }
+
+    /// Save the clocks and the pending named timers, scheduled with [Timers::after_named], so
+    /// that they can be restored exactly with [Timers::from_save]. Timers scheduled with
+    /// closures cannot be saved and are left out. Settings are not saved.
+    pub fn save(&self) -> TimersSave {
+        let mut timers: Vec<_> = self
+            .wheel
+            .iter()
+            .chain(self.realtime.iter())
+            .filter_map(|(target, timer)| {
+                let (name, _, params) = timer.named.as_deref()?;
+                Some((
+                    timer.handle,
+                    SavedTimer {
+                        target,
+                        realtime: timer.realtime,
+                        phase: timer.phase,
+                        tag: timer.tag,
+                        handler_name: name.clone(),
+                        params: params.clone(),
+                    },
+                ))
+            })
+            .collect();
+        timers.sort_by_key(|(handle, _)| *handle);
+        TimersSave {
+            elapsed: self.wheel.elapsed,
+            realtime_elapsed: self.realtime.elapsed,
+            scaled_behind: self.scaled_behind,
+            epochs: self.epochs.clone(),
+            timers: timers.into_iter().map(|(_, timer)| timer).collect(),
+        }
+    }
+
+    /// Construct a new [Timers] resource from a [TimersSave], with its clocks on the same
+    /// ticks and each saved timer firing on the same tick as before, looking the handlers up
+    /// by name in the registry. Timers get new handles. If a handler is no longer
+    /// registered, an error is logged and its timer is left out.
+    pub fn from_save(save: TimersSave, registry: &HandlerRegistry) -> Self {
+        let mut timers = Timers::default();
+        timers.wheel.elapsed = save.elapsed;
+        timers.realtime.elapsed = save.realtime_elapsed;
+        timers.scaled_behind = save.scaled_behind;
+        timers.epochs = save.epochs;
+        for saved in save.timers {
+            let handler = match registry.get(&saved.handler_name) {
+                Some(handler) => handler,
+                None => {
+                    error!(
+                        "no timer handler named {:?} is registered",
+                        saved.handler_name
+                    );
+                    continue;
+                }
+            };
+            let timer = Timer {
+                phase: saved.phase,
+                tag: saved.tag,
+                realtime: saved.realtime,
+                ..timers.named_timer(&saved.handler_name, handler, saved.params)
+            };
+            let elapsed = timers.clock(saved.realtime).elapsed;
+            timers.schedule(saved.target.saturating_sub(elapsed) as usize, timer);
+        }
+        timers
+    }
+
+    /// Construct a new [Timers] resource from timers previously extracted with
+    /// [Timers::extract]. Each timer fires after its remaining number of ticks, and keeps its
+    /// handle, so handles held from before the extraction stay valid.
+    pub fn from_pending(pending: PendingTimers) -> Self {
+        let mut timers = Timers::default();
+        let next_handle = pending.0.iter().map(|pending| pending.handle.0 + 1).max();
+        for pending in pending.0 {
+            let timer = Timer {
+                handle: pending.handle,
+                phase: pending.phase,
+                tag: pending.tag,
+                key: pending.key,
+                throttle_key: pending.throttle_key,
+                label: pending.label,
+                named: pending.named.map(Box::new),
+                repeats: pending.repeats,
+                realtime: pending.realtime,
+                ..timers.new_timer(pending.system)
+            };
+            let handle = timers.schedule(pending.remaining, timer);
+            if let Some(key) = pending.key {
+                timers.keyed.insert(key, handle);
+            }
+            if let Some(key) = pending.throttle_key {
+                timers.throttle_pending.insert(key, handle);
+            }
+        }
+        timers.next_handle = next_handle.unwrap_or(0);
+        timers
+    }
+
+    fn new_handle(&mut self) -> TimerHandle {
+        let handle = TimerHandle(self.next_handle);
+        self.next_handle += 1;
+        handle
+    }
+
+    /// Create a timer that calls a named handler, remembering the handler and parameters so
+    /// that the timer can be recreated by [Timers::duplicate_schedule].
+    fn named_timer(&mut self, name: &str, handler: TimerHandler, params: TimerParams) -> Timer {
+        let named = Some(Box::new((name.to_string(), handler, params.clone())));
+        let system = Box::new(move |world: &mut World| handler(world, &params));
+        Timer {
+            named,
+            ..self.new_timer(TimerSystem::Once(system))
+        }
+    }
+
+    /// Create a timer with a fresh handle and no metadata.
+    fn new_timer(&mut self, system: TimerSystem) -> Timer {
+        Timer {
+            handle: self.new_handle(),
+            phase: Phase::Normal,
+            tag: None,
+            key: None,
+            throttle_key: None,
+            system,
+            repeats: 0,
+            realtime: false,
+            named: None,
+            label: None,
+        }
+    }
+
+    fn schedule(&mut self, after: usize, timer: Timer) -> TimerHandle {
+        if self.paused && !self.schedule_while_paused {
+            panic!("cannot schedule a timer while paused");
+        }
+        if self.is_full() {
+            warn!(
+                "dropped timer {:?} because too many timers are pending",
+                timer.handle
+            );
+            return timer.handle;
+        }
+        self.schedule_unchecked(after, timer)
+    }
+
+    /// Schedule a timer even if scheduling while paused is disallowed.
+    fn schedule_unchecked(&mut self, after: usize, timer: Timer) -> TimerHandle {
+        let handle = timer.handle;
+        let clock = self.clock_mut(timer.realtime);
+        clock.schedule(after, timer);
+        let target = clock.elapsed + after as u64;
+        self.targets.insert(handle, target);
+        self.pending += 1;
+        self.total_scheduled += 1;
+        handle
+    }
+
+    /// Drop the bookkeeping for a timer that is no longer pending, either because it fired
+    /// or because it was cancelled.
+    fn forget(&mut self, timer: &Timer, fired: bool) {
+        self.pending -= 1;
+        if fired {
+            self.total_fired += 1;
+        } else {
+            self.total_cancelled += 1;
+        }
+        self.targets.remove(&timer.handle);
+        if let Some(key) = timer.key {
+            if self.keyed.get(&key) == Some(&timer.handle) {
+                self.keyed.remove(&key);
+            }
+        }
+        if let Some(key) = timer.throttle_key {
+            if self.throttle_pending.get(&key) == Some(&timer.handle) {
+                self.throttle_pending.remove(&key);
+            }
+        }
+    }
+
+    /// Hold back the timers of [Timers::throttle] for the key until `min_interval` ticks
+    /// after the tick being run.
+    fn throttle_fired(&mut self, key: u64, min_interval: usize) {
+        let fired = self.wheel.elapsed.saturating_sub(1);
+        let until = fired.saturating_add(min_interval as u64);
+        if let Some(previous) = self.throttled.insert(key, until) {
+            self.throttle_expiry.remove(&(previous, key));
+        }
+        self.throttle_expiry.insert((until, key));
+    }
+
+    /// Schedule a one-shot timer and record its handle under the key.
+    fn schedule_keyed(&mut self, key: u64, after: usize, system: BoxedSystem) -> TimerHandle {
+        let timer = Timer {
+            key: Some(key),
+            ..self.new_timer(TimerSystem::Once(system))
+        };
+        let handle = self.schedule(after, timer);
+        if self.targets.contains_key(&handle) {
+            self.keyed.insert(key, handle);
+        }
+        handle
+    }
+
+    /// Reschedule a repeating timer that fired on the given tick, unless it has reached the
+    /// maximum number of repeats.
+    fn repeat(&mut self, fired: u64, mut timer: Timer) {
+        timer.repeats += 1;
+        if let Some(max) = self.max_repeats {
+            if timer.repeats >= max {
+                warn!(
+                    "repeating timer {:?} cancelled after firing {} times",
+                    timer.handle, timer.repeats
+                );
+                self.forget(&timer, false);
+                return;
+            }
+        }
+        let interval = match timer.system {
+            TimerSystem::Repeating { interval, .. } => interval,
+            TimerSystem::Once(_) | TimerSystem::ReadOnly(_) => unreachable!(),
+        };
+        self.insert(fired + interval as u64, timer);
+    }
+
+    /// Insert a pending timer that fires on the given absolute tick.
+    fn insert(&mut self, target: u64, timer: Timer) {
+        self.targets.insert(timer.handle, target);
+        self.clock_mut(timer.realtime).insert(target, timer);
+    }
+
+    /// Return a pending timer, wherever it sits in either wheel.
+    fn get_mut(&mut self, handle: TimerHandle) -> Option<&mut Timer> {
+        let target = *self.targets.get(&handle)?;
+        match self
+            .wheel
+            .get_mut_at(target, |timer| timer.handle == handle)
+        {
+            Some(timer) => Some(timer),
+            None => self
+                .realtime
+                .get_mut_at(target, |timer| timer.handle == handle),
+        }
+    }
+
+    /// Remove a pending timer from the wheel without dropping its bookkeeping.
+    fn remove(&mut self, handle: TimerHandle) -> Option<Timer> {
+        let target = match self.targets.get(&handle) {
+            Some(&target) => target,
+            None => return self.remove_frozen(handle),
+        };
+        self.wheel
+            .remove_at(target, |timer| timer.handle == handle)
+            .or_else(|| {
+                self.realtime
+                    .remove_at(target, |timer| timer.handle == handle)
+            })
+    }
+
+    /// Remove a timer paused by [Timers::pause_group].
+    fn remove_frozen(&mut self, handle: TimerHandle) -> Option<Timer> {
+        let (&tag, paused) = self
+            .frozen
+            .iter_mut()
+            .find(|(_, paused)| paused.iter().any(|(_, timer)| timer.handle == handle))?;
+        let index = paused
+            .iter()
+            .position(|(_, timer)| timer.handle == handle)?;
+        let (_, timer) = paused.remove(index);
+        if paused.is_empty() {
+            self.frozen.remove(&tag);
+        }
+        Some(timer)
+    }
+
+    /// Cancel every pending timer that matches the predicate, returning how many there were.
+    fn cancel_matching<F>(&mut self, mut predicate: F) -> usize
+    where
+        F: FnMut(&Timer) -> bool,
+    {
+        let mut removed: Vec<_> = self
+            .wheel
+            .remove_all(&mut predicate)
+            .into_iter()
+            .chain(self.realtime.remove_all(&mut predicate))
+            .map(|(_, timer)| timer)
+            .collect();
+        for paused in self.frozen.values_mut() {
+            let (matching, rest): (Vec<_>, Vec<_>) = mem::take(paused)
+                .into_iter()
+                .partition(|(_, timer)| predicate(timer));
+            *paused = rest;
+            removed.extend(matching.into_iter().map(|(_, timer)| timer));
+        }
+        self.frozen.retain(|_, paused| !paused.is_empty());
+        for timer in &removed {
+            self.record(RecordedCall::Cancel {
+                handle: timer.handle,
+            });
+            self.forget(timer, false);
+        }
+        removed.len()
+    }
+
+    /// Return true if a timer has yet to run, including timers that have fired on the current
+    /// tick and timers paused by [Timers::pause_group].
+    fn is_pending(&self, handle: TimerHandle) -> bool {
+        self.targets.contains_key(&handle)
+            || self.in_flight.contains(&handle)
+            || self
+                .frozen
+                .values()
+                .flatten()
+                .any(|(_, timer)| timer.handle == handle)
+    }
+
+    /// Return the wheel of the scaled or the unscaled clock.
+    fn clock(&self, realtime: bool) -> &TimingWheelHierarchy<Timer> {
+        if realtime {
+            &self.realtime
+        } else {
+            &self.wheel
+        }
+    }
+
+    /// Return the wheel of the scaled or the unscaled clock mutably.
+    fn clock_mut(&mut self, realtime: bool) -> &mut TimingWheelHierarchy<Timer> {
+        if realtime {
+            &mut self.realtime
+        } else {
+            &mut self.wheel
+        }
+    }
+
+    /// Schedule the timers sent through a [TimerSender] since the last tick.
+    fn receive(&mut self) {
+        let sent: Vec<_> = self.receiver.get_mut().unwrap().try_iter().collect();
+        for (after, system) in sent {
+            if self.is_full() {
+                warn!("dropped a sent timer because too many timers are pending");
+                continue;
+            }
+            let timer = self.new_timer(TimerSystem::Once(system));
+            self.schedule_unchecked(after, timer);
+        }
+    }
+
+    /// Return true if the limit set with [Timers::set_max_pending] has been reached.
+    fn is_full(&self) -> bool {
+        self.max_pending.is_some_and(|max| self.pending >= max)
+    }
+
+    /// Advance the clock and return the timers that fire on this tick. The timers are still
+    /// pending until they are taken out of the batch by [Timers::start].
+    fn tick(&mut self) -> Slot<Timer> {
+        self.receive();
+        if cfg!(debug_assertions) {
+            self.check_leak();
+            self.check_totals();
+        }
+        let timers = self.wheel.tick();
+        self.begin_batch(timers)
+    }
+
+    /// Advance the unscaled clock and return the timers that fire on this tick.
+    fn tick_realtime(&mut self) -> Slot<Timer> {
+        let timers = self.realtime.tick();
+        self.begin_batch(timers)
+    }
+
+    /// Advance the clock and return the closures of the timers that fire on this tick.
+    fn advance(&mut self) -> Vec<BoxedSystem> {
+        let timers = self.tick();
+        self.due(timers)
+    }
+
+    /// Advance the unscaled clock and return the closures of the timers that fire on this
+    /// tick.
+    fn advance_realtime(&mut self) -> Vec<BoxedSystem> {
+        let timers = self.tick_realtime();
+        self.due(timers)
+    }
+
+    /// Start running a batch of timers that fired on the same tick.
+    fn begin_batch(&mut self, mut timers: Slot<Timer>) -> Slot<Timer> {
+        self.drop_unstarted();
+        // Handles are handed out in scheduling order and kept when a repeating timer is
+        // rescheduled, so they break ties the same way on every cycle.
+        timers.sort_by_key(|(_, timer)| (timer.phase, timer.handle));
+        self.in_flight = timers.iter().map(|(_, timer)| timer.handle).collect();
+        self.draining = true;
+        self.skipped.clear();
+        self.batch_ran = 0;
+        timers
+    }
+
+    /// Wrap each timer of a batch in a closure that takes it out of the batch and runs it,
+    /// grouping read-only timers that fire next to each other.
+    fn due(&mut self, timers: Slot<Timer>) -> Vec<BoxedSystem> {
+        let mut due: Vec<BoxedSystem> = Vec::with_capacity(timers.len());
+        let mut read_only = Vec::new();
+        for (target, timer) in timers {
+            if let TimerSystem::ReadOnly(_) = timer.system {
+                read_only.push((target, timer));
+                continue;
+            }
+            if !read_only.is_empty() {
+                let group = mem::take(&mut read_only);
+                due.push(Box::new(move |world: &mut World| {
+                    run_due_read_only(world, group)
+                }));
+            }
+            due.push(Box::new(move |world: &mut World| {
+                if let Some(timer) = get_timers_mut(world).start_budgeted(target, timer) {
+                    run_timer(world, target, timer);
+                }
+            }));
+        }
+        if !read_only.is_empty() {
+            due.push(Box::new(move |world: &mut World| {
+                run_due_read_only(world, read_only)
+            }));
+        }
+        due
+    }
+
+    /// Cancel the timers of the last batch whose closures were dropped without being run,
+    /// e.g. after being filtered out of [Timers::advance_one].
+    fn drop_unstarted(&mut self) {
+        for handle in mem::take(&mut self.in_flight) {
+            self.pending -= 1;
+            self.total_cancelled += 1;
+            self.targets.remove(&handle);
+            self.keyed.retain(|_, pending| *pending != handle);
+            self.throttle_pending
+                .retain(|_, pending| *pending != handle);
+        }
+    }
+
+    /// Return the number of scaled ticks to run for one real tick.
+    fn scaled_ticks(&mut self) -> usize {
+        self.scaled_behind += self.time_scale;
+        let ticks = self.scaled_behind as usize;
+        self.scaled_behind -= ticks as f64;
+        ticks
+    }
+
+    /// Take a timer out of the batch that fired on this tick before running it. Returns
+    /// false if the timer was cancelled before it could run.
+    fn start(&mut self, target: u64, timer: &Timer) -> bool {
+        if !self.in_flight.remove(&timer.handle) {
+            // The timer was cancelled along with the rest of an unfinished batch.
+            return false;
+        }
+        if self.skipped.remove(&timer.handle) {
+            self.forget(timer, false);
+            return false;
+        }
+        self.fires += 1;
+        if let Some(cap) = self.fire_log_cap {
+            if self.fire_log.len() < cap {
+                self.fire_log.push(FireRecord {
+                    tick: target,
+                    handle: timer.handle,
+                    tag: timer.tag,
+                    label: timer.label,
+                });
+            }
+        }
+        if !matches!(timer.system, TimerSystem::Repeating { .. }) {
+            self.forget(timer, true);
+        }
+        true
+    }
+
+    /// Add a call to the recording in progress, if any.
+    fn record(&mut self, call: RecordedCall) {
+        if let Some((start, recording)) = &mut self.recording {
+            // The clock may have been moved back since the recording started.
+            recording
+                .0
+                .push((self.wheel.elapsed.saturating_sub(*start), call));
+        }
+    }
+
+    /// Remove the replayed calls that are due to be made before the next tick.
+    fn due_calls(&mut self) -> Vec<RecordedCall> {
+        let mut due = Vec::new();
+        while let Some((tick, _)) = self.replaying.front() {
+            if *tick > self.wheel.elapsed {
+                break;
+            }
+            due.extend(self.replaying.pop_front().map(|(_, call)| call));
+        }
+        due
+    }
+
+    /// Make a call from a replayed recording.
+    fn reissue(&mut self, registry: &HandlerRegistry, call: RecordedCall) {
+        match call {
+            RecordedCall::AfterNamed {
+                after,
+                handler_name,
+                params,
+                handle,
+            } => {
+                if let Some(replayed) = self.after_named(registry, after, &handler_name, params) {
+                    self.replayed.insert(handle, replayed);
+                }
+            }
+            RecordedCall::Cancel { handle } => {
+                if let Some(&replayed) = self.replayed.get(&handle) {
+                    self.cancel(replayed);
+                }
+            }
+        }
+    }
+
+    /// Take a timer out of the batch that fired on this tick if the tick budget allows,
+    /// moving it to the next tick otherwise. Returns the timer if it should be run now.
+    fn start_budgeted(&mut self, target: u64, timer: Timer) -> Option<Timer> {
+        if self.batch_ran >= self.tick_budget.unwrap_or(usize::MAX) {
+            self.defer(timer);
+            return None;
+        }
+        if !self.start(target, &timer) {
+            return None;
+        }
+        self.batch_ran += 1;
+        Some(timer)
+    }
+
+    /// Move a timer that fired over the tick budget to the next tick, unless it was cancelled
+    /// while the tick was being run.
+    fn defer(&mut self, timer: Timer) {
+        if !self.in_flight.remove(&timer.handle) {
+            return;
+        }
+        if self.skipped.remove(&timer.handle) {
+            self.forget(&timer, false);
+            return;
+        }
+        let next = self.clock(timer.realtime).elapsed;
+        self.insert(next, timer);
+    }
+
+    /// Log an error if the lifetime counters do not add up to the number of pending timers.
+    fn check_totals(&self) {
+        let finished = self.total_fired + self.total_cancelled;
+        if self.total_scheduled != finished + self.pending as u64 {
+            error!(
+                "timer accounting diverged: {} scheduled, {} fired, {} cancelled, {} pending",
+                self.total_scheduled, self.total_fired, self.total_cancelled, self.pending
+            );
+        }
+    }
+
+    /// Warn once each time the number of pending timers crosses the leak threshold.
+    fn check_leak(&mut self) {
+        let threshold = match self.leak_threshold {
+            Some(threshold) => threshold,
+            None => return,
+        };
+        if self.pending <= threshold {
+            self.leak_warned = false;
+        } else if !self.leak_warned {
+            self.leak_warned = true;
+            warn!(
+                "{} timers are pending, exceeding the leak threshold of {}",
+                self.pending, threshold
+            );
+        }
+    }
+}
+
+/// Schedule already boxed timers, each after the given number of ticks, e.g. when restoring
+/// a saved schedule.
+impl Extend<(usize, BoxedSystem)> for Timers {
+    fn extend<I: IntoIterator<Item = (usize, BoxedSystem)>>(&mut self, iter: I) {
+        for (after, system) in iter {
+            let timer = self.new_timer(TimerSystem::Once(system));
+            self.schedule(after, timer);
+        }
+    }
+}
+
+/// A Bevy non-send resource that holds the closures of the timers scheduled with
+/// [Timers::after_nonsend], which cannot be kept in [Timers] as they are not `Send` or
+/// `Sync`. Each closure is run by a placeholder timer in [Timers], under the same handle.
+#[derive(Default)]
+struct NonSendTimers {
+    timers: BTreeMap<TimerHandle, NonSendBoxedSystem>,
+    /// The handles of placeholder timers that were dropped, whose closures are to be dropped
+    /// as well.
+    dropped: Arc<Mutex<Vec<TimerHandle>>>,
+}
+
+/// Held by the placeholder timer of [Timers::after_nonsend], so that the closure it stands
+/// in for is dropped when the placeholder is dropped without running, e.g. when cancelled.
+struct NonSendGuard {
+    handle: TimerHandle,
+    dropped: Arc<Mutex<Vec<TimerHandle>>>,
+}
+
+impl NonSendGuard {
+    /// Run the closure of the timer.
+    fn run(self, world: &mut World) {
+        let timer = get_nonsend_timers_mut(world).timers.remove(&self.handle);
+        if let Some(timer) = timer {
+            timer(world);
+        }
+    }
+}
+
+impl Drop for NonSendGuard {
+    fn drop(&mut self) {
+        self.dropped.lock().unwrap().push(self.handle);
+    }
+}
+
+/// Return the [NonSendTimers] resource of the world mutably.
+fn get_nonsend_timers_mut(world: &mut World) -> Mut<'_, NonSendTimers> {
+    world
+        .get_non_send_resource_mut::<NonSendTimers>()
+        .expect("the NonSendTimers resource is missing; add the TimerPlugin first")
+}
+
+/// Drop the closures of the timers of [Timers::after_nonsend] that were cancelled.
+fn drop_cancelled_nonsend(world: &mut World) {
+    let mut non_send = match world.get_non_send_resource_mut::<NonSendTimers>() {
+        Some(non_send) => non_send,
+        None => return,
+    };
+    let non_send = &mut *non_send;
+    for handle in mem::take(&mut *non_send.dropped.lock().unwrap()) {
+        non_send.timers.remove(&handle);
+    }
+}
+
+/// Return the [Timers] resource of the world. It is a non-send resource when the
+/// `single_thread` feature is enabled.
+fn get_timers(world: &World) -> &Timers {
+    #[cfg(not(feature = "single_thread"))]
+    let timers = world.get_resource::<Timers>();
+    #[cfg(feature = "single_thread")]
+    let timers = world.get_non_send_resource::<Timers>();
+    timers.expect("Failed")
+}
+
+/// Return the [Timers] resource of the world mutably.
+fn get_timers_mut(world: &mut World) -> Mut<'_, Timers> {
+    #[cfg(not(feature = "single_thread"))]
+    let timers = world.get_resource_mut::<Timers>();
+    #[cfg(feature = "single_thread")]
+    let timers = world.get_non_send_resource_mut::<Timers>();
+    timers.expect("Failed")
+}
+
+/// Advance the timers by one tick and run every timer that fires on it.
+fn run_tick(world: &mut World) {
+    let calls = get_timers_mut(world).due_calls();
+    if !calls.is_empty() {
+        world.resource_scope(|world, registry: Mut<HandlerRegistry>| {
+            let mut timers = get_timers_mut(world);
+            for call in calls {
+                timers.reissue(&registry, call);
+            }
+        });
+    }
+    drop_cancelled_nonsend(world);
+    let hooks = mem::take(&mut get_timers_mut(world).first_tick_hooks);
+    for hook in hooks {
+        hook(world);
+    }
+    let due = get_timers_mut(world).advance();
+    run_batch(world, due);
+    run_wrap_hooks(world);
+}
+
+/// Toggle a component for [Timers::blink], and schedule the next toggle if there are any
+/// left.
+fn blink<C>(world: &mut World, entity: Entity, interval: usize, remaining: usize)
+where
+    C: Component + Default,
+{
+    let mut entity_mut = match world.get_entity_mut(entity) {
+        Some(entity_mut) => entity_mut,
+        None => return,
+    };
+    if entity_mut.contains::<C>() {
+        entity_mut.remove::<C>();
+    } else {
+        entity_mut.insert(C::default());
+    }
+    if remaining > 1 {
+        // The clock has already moved past the current tick, so this fires `interval`
+        // ticks after it.
+        schedule_step(world, interval - 1, "blink", move |world: &mut World| {
+            blink::<C>(world, entity, interval, remaining - 1)
+        });
+    }
+}
+
+/// Show a frame of an animation for [Timers::animate], and schedule the next frame if the
+/// animation has not finished.
+fn animate<C, F>(
+    world: &mut World,
+    entity: Entity,
+    frame_ticks: usize,
+    frames: usize,
+    looping: bool,
+    frame: usize,
+    mut set_index: F,
+) where
+    C: Component,
+    F: FnMut(&mut C, usize) + MaybeSendSync + 'static,
+{
+    let mut component = match world.get_mut::<C>(entity) {
+        Some(component) => component,
+        None => return,
+    };
+    set_index(&mut component, frame);
+    let next = if frame + 1 < frames {
+        frame + 1
+    } else if looping {
+        0
+    } else {
+        return;
+    };
+    // The clock has already moved past the current tick, so this fires `frame_ticks` ticks
+    // after it.
+    schedule_step(
+        world,
+        frame_ticks - 1,
+        "animation",
+        move |world: &mut World| {
+            animate::<C, F>(world, entity, frame_ticks, frames, looping, next, set_index)
+        },
+    );
+}
+
+/// Take a step of [Timers::count_down], and schedule the next step if the count has not
+/// reached zero.
+fn count_down<T, D>(world: &mut World, remaining: usize, step: usize, mut on_tick: T, on_done: D)
+where
+    T: FnMut(&mut World, usize) + MaybeSendSync + 'static,
+    D: FnOnce(&mut World) + MaybeSendSync + 'static,
+{
+    if remaining == 0 {
+        on_done(world);
+        return;
+    }
+    on_tick(world, remaining);
+    // The clock has already moved past the current tick, so this fires `step` ticks after it.
+    schedule_step(world, step - 1, "count down", move |world: &mut World| {
+        count_down(world, remaining - 1, step, on_tick, on_done)
+    });
+}
+
+/// Take a step of [Timers::tween], and schedule the next step if the tween has not finished.
+fn tween<F>(world: &mut World, step: usize, duration: usize, mut setter: F)
+where
+    F: FnMut(&mut World, f32) + MaybeSendSync + 'static,
+{
+    if step >= duration {
+        setter(world, 1.0);
+        return;
+    }
+    setter(world, step as f32 / duration as f32);
+    // The clock has already moved past the current tick, so this fires on the next one.
+    schedule_step(world, 0, "tween", move |world: &mut World| {
+        tween(world, step + 1, duration, setter)
+    });
+}
+
+/// Schedule the next step of a timer sequence such as [Timers::blink] from inside the
+/// running step. If the step cannot be scheduled, e.g. because too many timers are pending,
+/// the rest of the sequence is dropped with a warning rather than panicking mid-tick.
+fn schedule_step<S>(world: &mut World, after: usize, sequence: &str, step: S)
+where
+    S: FnOnce(&mut World) + MaybeSendSync + 'static,
+{
+    if let Err(err) = get_timers_mut(world).try_after(after, step) {
+        warn!("dropped the rest of a {}: {}", sequence, err);
+    }
+}
+
+/// Run a timer that fired on the given tick, rescheduling it if it repeats.
+fn run_timer(world: &mut World, fired: u64, timer: Timer) {
+    match timer.system {
+        TimerSystem::Once(system) => system(world),
+        TimerSystem::ReadOnly(system) => run_read_only(world, &mut vec![system]),
+        TimerSystem::Repeating {
+            mut system,
+            interval,
+        } => {
+            system(world);
+            let timer = Timer {
+                system: TimerSystem::Repeating { system, interval },
+                ..timer
+            };
+            get_timers_mut(world).repeat(fired, timer);
+        }
+    }
+}
+
+/// Run the closures of a batch of timers that fired on the same tick.
+fn run_batch(world: &mut World, due: Vec<BoxedSystem>) {
+    for system in due {
+        system(world);
+    }
+    get_timers_mut(world).draining = false;
+}
+
+/// Take a group of read-only timers that fired next to each other out of their batch, and
+/// run the ones that have not been cancelled together.
+fn run_due_read_only(world: &mut World, timers: Vec<(u64, Timer)>) {
+    let mut systems = Vec::with_capacity(timers.len());
+    for (target, timer) in timers {
+        if let Some(timer) = get_timers_mut(world).start_budgeted(target, timer) {
+            if let TimerSystem::ReadOnly(system) = timer.system {
+                systems.push(system);
+            }
+        }
+    }
+    run_read_only(world, &mut systems);
+}
+
+/// Run a group of read-only timers, in parallel with the `rayon` feature, and then apply the
+/// commands they queued in order.
+fn run_read_only(world: &mut World, systems: &mut Vec<BoxedReadSystem>) {
+    if systems.is_empty() {
+        return;
+    }
+    let queues: Vec<CommandQueue> = {
+        let world: &World = world;
+        let run = |system: BoxedReadSystem| {
+            let mut queue = CommandQueue::default();
+            system(world, &mut Commands::new(&mut queue, world));
+            queue
+        };
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            systems.par_drain(..).map(run).collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            systems.drain(..).map(run).collect()
+        }
+    };
+    for mut queue in queues {
+        queue.apply(world);
+    }
+}
+
+/// Advance the unscaled clock by one tick, and the scaled clock by as many ticks as the time
+/// scale calls for.
+fn run_real_tick(world: &mut World) {
+    let due = get_timers_mut(world).advance_realtime();
+    run_batch(world, due);
+    for _ in 0..get_timers_mut(world).scaled_ticks() {
+        run_tick(world);
+    }
+}
+
+/// Run the callbacks of every level that wrapped around on the last tick.
+fn run_wrap_hooks(world: &mut World) {
+    let mut timers = get_timers_mut(world);
+    if timers.wrap_hooks.is_empty() || !timers.wheel.wrapped(0) {
+        return;
+    }
+    let mut hooks = mem::take(&mut timers.wrap_hooks);
+    for (level, hook) in hooks.iter_mut() {
+        let wrapped = get_timers(world).wheel.wrapped(*level);
+        if wrapped {
+            hook(world);
+        }
+    }
+    // Keep any callbacks registered by the callbacks themselves.
+    let mut timers = get_timers_mut(world);
+    hooks.append(&mut timers.wrap_hooks);
+    timers.wrap_hooks = hooks;
+}
+
+/// The label of the stage that runs timers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, StageLabel)]
+pub struct TimerStage;
+
+/// The labels of the stages the timer plugins add directly before and after the
+/// [TimerStage]. Systems added to them run every update, whether or not any ticks run, and
+/// are guaranteed to bracket the timers, e.g. to snapshot positions before timers fire and
+/// clean up after them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, StageLabel)]
+pub enum TimerDrainStage {
+    PreDrain,
+    PostDrain,
+}
+
+/// Timer closures take the [World] mutably and non-send timers in particular must only be
+/// run on the main thread, so in debug builds the timer stages check that they run on the
+/// thread that built the app. This catches the stage being misplaced into a parallel
+/// context, at no cost in release builds.
+fn debug_assert_main_thread(main_thread: ThreadId) {
+    debug_assert_eq!(
+        thread::current().id(),
+        main_thread,
+        "timers must be run on the thread that built the app"
+    );
+}
+
+/// Run the ticks owed for this update, as the [TimerStage] does each update. This can be
+/// added to a stage as an exclusive system instead, see [TimerPlugin::exclusive_system_in].
+pub fn run_timers(world: &mut World) {
+    let focused = world
+        .get_resource::<Windows>()
+        .and_then(Windows::get_primary)
+        .is_none_or(Window::is_focused);
+    get_timers_mut(world).focused = focused;
+    if !get_timers(world).is_clock_running() {
+        // Don't owe any ticks for the time spent paused.
+        get_timers_mut(world).behind = 0.0;
+        return;
+    }
+    for _ in 0..update_ticks(world) {
+        run_real_tick(world);
+    }
+}
+
+/// Return the number of ticks to run this update.
+fn update_ticks(world: &mut World) -> usize {
+    let delta = world.get_resource::<Time>().map(Time::delta_seconds_f64);
+    let mut timers = get_timers_mut(world);
+    let rate = if !timers.focused && timers.unfocused_policy == UnfocusedPolicy::KeepRate {
+        Some(timers.tick_rate)
+    } else {
+        timers.catch_up_rate
+    };
+    let (rate, delta) = match (rate, delta) {
+        (Some(rate), Some(delta)) => (rate, delta),
+        _ => {
+            timers.behind = 0.0;
+            return 1;
+        }
+    };
+    timers.behind += delta * rate;
+    let ticks = (timers.behind as usize).max(1);
+    match timers.max_catch_up {
+        Some(max) if ticks > max => {
+            timers.behind = 0.0;
+            max.max(1)
+        }
+        _ => {
+            timers.behind = (timers.behind - ticks as f64).max(0.0);
+            ticks
+        }
+    }
+}
+
+struct RunTimers {
+    main_thread: ThreadId,
+}
+
+impl Stage for RunTimers {
+    fn run(&mut self, world: &mut World) {
+        debug_assert_main_thread(self.main_thread);
+        run_timers(world);
+    }
+}
+
+/// A Bevy plugin that adds the [Timers] and [HandlerRegistry] resources and
+/// a scheduler to execute timers each game update.
+pub struct TimerPlugin;
+
+impl TimerPlugin {
+    /// Run the timers in a stage placed directly after the given stage, rather than after
+    /// all of the other stages. For example, `TimerPlugin::run_in(CoreStage::First)` fires
+    /// timers before input is processed and any of the game's systems run.
+    pub fn run_in<L>(label: L) -> TimerPluginAfter<L>
+    where
+        L: StageLabel + Clone,
+    {
+        TimerPluginAfter(label)
+    }
+
+    /// Advance the timers by the number of ticks the given [TickSource] asks for each
+    /// update, instead of once per update. The catch-up rate and unfocused policy of
+    /// [Timers] only apply to the default source, and are ignored.
+    pub fn with_source<S: TickSource>(source: S) -> TimerPluginWith<S> {
+        TimerPluginWith(Mutex::new(Some(source)))
+    }
+
+    /// Advance the timers once per fixed physics step of 1/60th of a second, the step most
+    /// physics plugins default to, instead of once per update, so that timers stay in sync
+    /// with the physics simulation whatever the frame rate. This is a [FixedTimestep] source,
+    /// see [TimerPlugin::with_source], and pairs with [Timers::after_physics_steps].
+    pub fn physics_stepped() -> TimerPluginWith<FixedTimestep> {
+        TimerPlugin::with_source(FixedTimestep::new(Duration::from_secs_f64(
+            1.0 / DEFAULT_TICK_RATE,
+        )))
+    }
+
+    /// Run the timers with [run_timers] as an exclusive system at the end of an existing
+    /// stage, e.g. `TimerPlugin::exclusive_system_in(CoreStage::Last)`, rather than in a
+    /// [TimerStage] of their own. The [TimerDrainStage] stages are not added, so systems
+    /// that must bracket the timers go in the stages before and after instead.
+    pub fn exclusive_system_in<L>(label: L) -> TimerPluginExclusive<L>
+    where
+        L: StageLabel + Clone,
+    {
+        TimerPluginExclusive(label)
+    }
+
+    /// Run the timers directly after [CoreStage::PostUpdate], where Bevy propagates
+    /// transforms, so that timers see the final [GlobalTransform] of every entity for the
+    /// frame. Bevy 0.6 orders systems with stages rather than system sets, so to fire timers
+    /// before another plugin's stages, such as physics, pass the stage that precedes them to
+    /// [TimerPlugin::run_in] instead.
+    pub fn after_transform_propagate() -> TimerPluginAfter<CoreStage> {
+        TimerPlugin::run_in(CoreStage::PostUpdate)
+    }
+}
+
+impl Plugin for TimerPlugin {
+    fn build(&self, app: &mut App) {
+        add_timer_resources(app);
+        app.add_stage(
+            TimerStage,
+            RunTimers {
+                main_thread: thread::current().id(),
+            },
+        );
+        add_drain_stages(app);
+    }
+}
+
+/// A [TimerPlugin] that runs the timers directly after another stage. Created with
+/// [TimerPlugin::run_in].
+pub struct TimerPluginAfter<L>(L);
+
+impl<L> Plugin for TimerPluginAfter<L>
+where
+    L: StageLabel + Clone,
+{
+    fn build(&self, app: &mut App) {
+        add_timer_resources(app);
+        app.add_stage_after(
+            self.0.clone(),
+            TimerStage,
+            RunTimers {
+                main_thread: thread::current().id(),
+            },
+        );
+        add_drain_stages(app);
+    }
+}
+
+/// A [TimerPlugin] that runs the timers as an exclusive system in another stage. Created
+/// with [TimerPlugin::exclusive_system_in].
+pub struct TimerPluginExclusive<L>(L);
+
+impl<L> Plugin for TimerPluginExclusive<L>
+where
+    L: StageLabel + Clone,
+{
+    fn build(&self, app: &mut App) {
+        add_timer_resources(app);
+        app.add_system_to_stage(self.0.clone(), run_timers.exclusive_system().at_end());
+    }
+}
+
+/// A [TimerPlugin] that advances the timers with a custom [TickSource]. Created with
+/// [TimerPlugin::with_source].
+pub struct TimerPluginWith<S>(Mutex<Option<S>>);
+
+impl<S: TickSource> Plugin for TimerPluginWith<S> {
+    fn build(&self, app: &mut App) {
+        let source = self
+            .0
+            .lock()
+            .expect("Failed")
+            .take()
+            .expect("a TimerPlugin with a tick source can only be added once");
+        add_timer_resources(app);
+        app.add_stage(
+            TimerStage,
+            RunSourcedTimers {
+                main_thread: thread::current().id(),
+                source,
+            },
+        );
+        add_drain_stages(app);
+    }
+}
+
+/// Decides how many ticks the timers advance by each time the timer stage runs, for use
+/// with [TimerPlugin::with_source]. Implement it to drive the timers from anything else,
+/// such as messages from a server.
+pub trait TickSource: Send + Sync + 'static {
+    /// Return the number of ticks to advance by on this run of the timer stage. This is
+    /// called even while the timers are paused, in which case the ticks are dropped.
+    fn ticks(&mut self, world: &World) -> usize;
+}
+
+/// A [TickSource] that advances by one tick every update, like the default [TimerPlugin].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PerUpdate;
+
+impl TickSource for PerUpdate {
+    fn ticks(&mut self, _world: &World) -> usize {
+        1
+    }
+}
+
+/// A [TickSource] that advances by one tick for every `step` of time elapsed according to
+/// Bevy's [Time] resource, carrying the remainder over to later updates.
+#[derive(Clone, Copy, Debug)]
+pub struct FixedTimestep {
+    step: Duration,
+    accumulated: Duration,
+}
+
+impl FixedTimestep {
+    /// Create a source that advances by one tick every `step`.
+    pub fn new(step: Duration) -> Self {
+        assert!(!step.is_zero(), "fixed timestep must be longer than zero");
+        FixedTimestep {
+            step,
+            accumulated: Duration::ZERO,
+        }
+    }
+}
+
+impl TickSource for FixedTimestep {
+    fn ticks(&mut self, world: &World) -> usize {
+        if let Some(time) = world.get_resource::<Time>() {
+            self.accumulated += time.delta();
+        }
+        let ticks = (self.accumulated.as_nanos() / self.step.as_nanos()) as usize;
+        self.accumulated -= self.step * ticks as u32;
+        ticks
+    }
+}
+
+/// A [TickSource] that advances by one tick for every event of type `E` sent since the last
+/// run. The event must be added to the app with `add_event`.
+pub struct EventDriven<E: Resource> {
+    events: ManualEventReader<E>,
+}
+
+impl<E: Resource> Default for EventDriven<E> {
+    fn default() -> Self {
+        EventDriven {
+            events: ManualEventReader::default(),
+        }
+    }
+}
+
+impl<E: Resource> TickSource for EventDriven<E> {
+    fn ticks(&mut self, world: &World) -> usize {
+        match world.get_resource::<Events<E>>() {
+            Some(events) => self.events.iter(events).count(),
+            None => 0,
+        }
+    }
+}
+
+struct RunSourcedTimers<S> {
+    main_thread: ThreadId,
+    source: S,
+}
+
+impl<S: TickSource> Stage for RunSourcedTimers<S> {
+    fn run(&mut self, world: &mut World) {
+        debug_assert_main_thread(self.main_thread);
+        let ticks = self.source.ticks(world);
+        if get_timers(world).paused {
+            return;
+        }
+        for _ in 0..ticks {
+            run_real_tick(world);
+        }
+    }
+}
+
+/// Insert the resources shared by all of the timer plugins.
+fn add_timer_resources(app: &mut App) {
+    #[cfg(not(feature = "single_thread"))]
+    app.world.insert_resource(Timers::default());
+    #[cfg(feature = "single_thread")]
+    app.world.insert_non_send(Timers::default());
+    app.world.insert_non_send(NonSendTimers::default());
+    app.world.insert_resource(HandlerRegistry::default());
+}
+
+/// Add the stages that bracket the [TimerStage].
+fn add_drain_stages(app: &mut App) {
+    app.add_stage_before(
+        TimerStage,
+        TimerDrainStage::PreDrain,
+        SystemStage::parallel(),
+    )
+    .add_stage_after(
+        TimerStage,
+        TimerDrainStage::PostDrain,
+        SystemStage::parallel(),
+    );
+}
+
+/// An event that advances the timers of a [TurnTimerPlugin] by one tick.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NextTurn;
+
+/// A Bevy plugin for turn-based games. It adds the same resources as [TimerPlugin], but
+/// the timers only advance by one tick for each [NextTurn] event sent, instead of once
+/// every game update. A timer scheduled after `n` ticks therefore fires on the `n + 1`th
+/// turn from now.
+pub struct TurnTimerPlugin;
+
+impl Plugin for TurnTimerPlugin {
+    fn build(&self, app: &mut App) {
+        add_timer_resources(app);
+        app.add_event::<NextTurn>();
+        app.add_stage(
+            TimerStage,
+            RunSourcedTimers {
+                main_thread: thread::current().id(),
+                source: EventDriven::<NextTurn>::default(),
+            },
+        );
+        add_drain_stages(app);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::utils::tracing::field::{Field, Visit};
+    use bevy::utils::tracing::{self, span, Event, Level, Metadata, Subscriber};
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    /// Values pushed by timers, shared with the test that scheduled them.
+    type Log<T> = Arc<Mutex<Vec<T>>>;
+
+    fn log<T>() -> Log<T> {
+        Arc::new(Mutex::new(Vec::new()))
+    }
+
+    fn logged<T: Clone>(log: &Log<T>) -> Vec<T> {
+        log.lock().unwrap().clone()
+    }
+
+    /// Return a timer that pushes the value to the log.
+    fn push<T>(log: &Log<T>, value: T) -> impl FnOnce(&mut World) + Send + Sync + 'static
+    where
+        T: Send + Sync + 'static,
+    {
+        let log = log.clone();
+        move |_| log.lock().unwrap().push(value)
+    }
+
+    /// Return a timer that pushes the tick it fires on to the log, counted from zero like
+    /// the delay passed to [Timers::after].
+    fn push_tick(log: &Log<u64>) -> impl FnOnce(&mut World) + Send + Sync + 'static {
+        let log = log.clone();
+        move |world| log.lock().unwrap().push(fired_on(world))
+    }
+
+    /// Return a repeating timer that pushes each tick it fires on to the log.
+    fn push_ticks(log: &Log<u64>) -> impl FnMut(&mut World) + Send + Sync + 'static {
+        let log = log.clone();
+        move |world| log.lock().unwrap().push(fired_on(world))
+    }
+
+    /// Return the tick of the scaled clock that the running timer fired on.
+    fn fired_on(world: &World) -> u64 {
+        get_timers(world).elapsed_ticks() - 1
+    }
+
+    /// Build an app that runs the timers once per update.
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugin(TimerPlugin);
+        app
+    }
+
+    fn timers(app: &mut App) -> Mut<'_, Timers> {
+        get_timers_mut(&mut app.world)
+    }
+
+    fn update(app: &mut App, updates: usize) {
+        for _ in 0..updates {
+            app.update();
+        }
+    }
+
+    /// A subscriber that keeps the message of every event logged while it is the default.
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<(Level, String)>>>);
+
+    impl Subscriber for Captured {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+            span::Id::from_u64(1)
+        }
+
+        fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut message = String::new();
+            event.record(&mut Message(&mut message));
+            let level = *event.metadata().level();
+            self.0.lock().unwrap().push((level, message));
+        }
+
+        fn enter(&self, _: &span::Id) {}
+
+        fn exit(&self, _: &span::Id) {}
+    }
+
+    struct Message<'a>(&'a mut String);
+
+    impl Visit for Message<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            if field.name() == "message" {
+                *self.0 = format!("{:?}", value);
+            }
+        }
+    }
+
+    /// Run `f` and return the messages it logged at the given level.
+    fn captured(level: Level, f: impl FnOnce()) -> Vec<String> {
+        let subscriber = Captured::default();
+        tracing::subscriber::with_default(subscriber.clone(), f);
+        let events = subscriber.0.lock().unwrap();
+        events
+            .iter()
+            .filter(|(logged, _)| *logged == level)
+            .map(|(_, message)| message.clone())
+            .collect()
+    }
+
+    #[test]
+    fn leak_warning_fires_once_per_crossing() {
+        let mut app = app();
+        timers(&mut app).set_leak_threshold(Some(3));
+        for _ in 0..5 {
+            timers(&mut app).after(100, |_| {});
+        }
+        let warnings = captured(Level::WARN, || update(&mut app, 3));
+        assert_eq!(
+            warnings,
+            ["5 timers are pending, exceeding the leak threshold of 3"]
+        );
+        assert!(timers(&mut app).leak_warned);
+
+        timers(&mut app).cancel_where(|_, _, _| true);
+        update(&mut app, 1);
+        assert!(!timers(&mut app).leak_warned);
+        for _ in 0..4 {
+            timers(&mut app).after(100, |_| {});
+        }
+        let warnings = captured(Level::WARN, || update(&mut app, 1));
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn leak_warning_can_be_disabled() {
+        let mut app = app();
+        timers(&mut app).set_leak_threshold(None);
+        for _ in 0..10 {
+            timers(&mut app).after(100, |_| {});
+        }
+        let warnings = captured(Level::WARN, || update(&mut app, 1));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn nonsend_timer_accesses_nonsend_resource() {
+        let mut app = app();
+        app.world.insert_non_send(Rc::new(Cell::new(0u32)));
+        let fired = Rc::new(Cell::new(None));
+        let seen = fired.clone();
+        Timers::after_nonsend(&mut app.world, 2, move |world| {
+            let count = world.get_non_send_resource::<Rc<Cell<u32>>>().unwrap();
+            count.set(count.get() + 1);
+            seen.set(Some(fired_on(world)));
+        });
+        update(&mut app, 5);
+        let count = app.world.get_non_send_resource::<Rc<Cell<u32>>>().unwrap();
+        assert_eq!(count.get(), 1);
+        assert_eq!(fired.get(), Some(2));
+    }
+
+    #[test]
+    fn nonsend_timers_share_the_clock_of_timers() {
+        let mut app = app();
+        let fired = log();
+        timers(&mut app).set_elapsed_ticks(1000);
+        Timers::after_nonsend(&mut app.world, 3, push_tick(&fired));
+        timers(&mut app).after(3, push_tick(&fired));
+        for timer in timers(&mut app).advance_one() {
+            timer(&mut app.world);
+        }
+        update(&mut app, 4);
+        assert_eq!(logged(&fired), [1003, 1003]);
+    }
+
+    #[test]
+    fn nonsend_timers_can_be_cancelled() {
+        let mut app = app();
+        let fired = Rc::new(Cell::new(false));
+        let seen = fired.clone();
+        let handle = Timers::after_nonsend(&mut app.world, 1, move |_| seen.set(true));
+        assert!(timers(&mut app).cancel(handle));
+        Timers::after_nonsend(&mut app.world, 1, |_| {});
+        let non_send = app.world.get_non_send_resource::<NonSendTimers>().unwrap();
+        assert_eq!(non_send.timers.len(), 1);
+        update(&mut app, 3);
+        assert!(!fired.get());
+    }
+
+    #[test]
+    fn count_tag_counts_pending_timers_per_tag() {
+        let mut app = app();
+        let mut timers = timers(&mut app);
+        timers.after_tagged(1, 7, |_| {});
+        timers.after_tagged(5, 7, |_| {});
+        timers.after_tagged(100, 7, |_| {});
+        timers.after_tagged(1, 8, |_| {});
+        timers.after(1, |_| {});
+        assert_eq!((timers.count_tag(7), timers.count_tag(8)), (3, 1));
+        assert_eq!(timers.count_tag(9), 0);
+        update(&mut app, 2);
+        let timers = get_timers(&app.world);
+        assert_eq!((timers.count_tag(7), timers.count_tag(8)), (2, 0));
+    }
+
+    /// Schedule a mix of timers, each pushing its name to the log when it runs.
+    fn schedule_mix(timers: &mut Timers, fired: &Log<&'static str>) {
+        timers.after(3, push(fired, "normal"));
+        timers.after_phased(3, Phase::Late, push(fired, "late"));
+        timers.after_phased(3, Phase::Early, push(fired, "early"));
+        timers.after_tagged(1, 9, push(fired, "tagged"));
+        timers.after_keyed(4, 6, push(fired, "keyed"));
+        let repeats = fired.clone();
+        timers.every(2, move |_| repeats.lock().unwrap().push("every"));
+    }
+
+    /// Run the app one update at a time, returning what the log holds after each update.
+    fn history<T: Clone>(app: &mut App, fired: &Log<T>, updates: usize) -> Vec<Vec<T>> {
+        (0..updates)
+            .map(|_| {
+                app.update();
+                logged(fired)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn extracted_timers_fire_the_same_in_a_fresh_resource() {
+        let mut original = app();
+        let original_fired = log();
+        schedule_mix(&mut timers(&mut original), &original_fired);
+        update(&mut original, 1);
+
+        let mut reloaded = app();
+        let reloaded_fired = log();
+        schedule_mix(&mut timers(&mut reloaded), &reloaded_fired);
+        update(&mut reloaded, 1);
+        let pending = timers(&mut reloaded).extract();
+        assert!(timers(&mut reloaded).is_empty());
+        *timers(&mut reloaded) = Timers::from_pending(pending);
+
+        assert_eq!(
+            history(&mut original, &original_fired, 8),
+            history(&mut reloaded, &reloaded_fired, 8)
+        );
+    }
+
+    #[test]
+    fn extract_keeps_the_metadata_and_order_of_timers() {
+        let mut timers = Timers::default();
+        timers.after_phased(2, Phase::Late, |_| {});
+        timers.after_phased(2, Phase::Early, |_| {});
+        let labeled = timers.after_labeled(1, "label", |_| {});
+        timers.after_keyed(5, 3, |_| {});
+        timers.after_tagged(2, 8, |_| {});
+
+        let mut before = Timers::default();
+        before.after_phased(2, Phase::Late, |_| {});
+        before.after_phased(2, Phase::Early, |_| {});
+        before.after_labeled(1, "label", |_| {});
+        before.after_keyed(5, 3, |_| {});
+        before.after_tagged(2, 8, |_| {});
+        let pending = timers.extract();
+        let order: Vec<_> = pending
+            .0
+            .iter()
+            .map(|timer| (timer.remaining, timer.phase, timer.tag))
+            .collect();
+        assert_eq!(
+            order,
+            [
+                (1, Phase::Normal, None),
+                (2, Phase::Early, None),
+                (2, Phase::Normal, Some(8)),
+                (2, Phase::Late, None),
+                (3, Phase::Normal, None),
+            ]
+        );
+        assert_eq!(pending.0[0].label, Some("label"));
+        assert_eq!(pending.0[4].key, Some(5));
+
+        let mut restored = Timers::from_pending(pending);
+        assert!(restored.schedule_eq(&before));
+        assert_eq!(restored.remaining(labeled), Some(1));
+        // The key is still taken by the restored timer.
+        let kept = restored.after_keyed(5, 10, |_| {});
+        assert_eq!(restored.remaining(kept), Some(3));
+    }
+
+    #[test]
+    fn after_checked_rejects_long_intervals() {
+        let mut timers = Timers::default();
+        let max = TimingWheelHierarchy::<Timer>::MAX_INTERVAL;
+        assert_eq!(
+            timers.after_checked(max + 1, |_| {}).err(),
+            Some(TimerError::IntervalTooLong { max })
+        );
+        assert!(timers.is_empty());
+        assert!(timers.after_checked(max, |_| {}).is_ok());
+    }
+
+    #[test]
+    fn after_checked_rejects_timers_while_paused_if_disallowed() {
+        let mut timers = Timers::default();
+        timers.pause();
+        assert!(timers.after_checked(1, |_| {}).is_ok());
+        timers.set_schedule_while_paused(false);
+        assert_eq!(
+            timers.after_checked(1, |_| {}).err(),
+            Some(TimerError::Paused)
+        );
+        assert_eq!(timers.len(), 1);
+    }
+
+    #[test]
+    fn after_checked_schedules_valid_timers() {
+        let mut app = app();
+        let fired = log();
+        let handle = timers(&mut app).after_checked(2, push_tick(&fired));
+        assert!(handle.is_ok());
+        update(&mut app, 3);
+        assert_eq!(logged(&fired), [2]);
+    }
+
+    /// Return the level and slot of the wheel that hold the given timer.
+    fn position(wheel: &TimingWheelHierarchy<u64>, id: u64) -> Option<(usize, usize)> {
+        wheel.level.iter().enumerate().find_map(|(level, wheel)| {
+            let offset = wheel
+                .ring
+                .iter()
+                .position(|slot| slot.iter().any(|(_, timer)| *timer == id))?;
+            Some((level, offset))
+        })
+    }
+
+    #[test]
+    fn schedule_at_level_uses_the_slot_covering_the_target() {
+        let mut wheel = TimingWheelHierarchy::default();
+        for level in 0..LEVELS {
+            let span = 1 << (SLOT_BITS * level);
+            wheel.schedule_at_level(level, 5 * span, level as u64);
+            wheel.schedule_at_level(level, 6 * span - 1, 10 + level as u64);
+            assert_eq!(position(&wheel, level as u64), Some((level, 5)));
+            assert_eq!(position(&wheel, 10 + level as u64), Some((level, 5)));
+        }
+        // The slot wraps around with the digit of the level.
+        wheel.schedule_at_level(0, 64 + 3, 20);
+        assert_eq!(position(&wheel, 20), Some((0, 3)));
+    }
+
+    #[test]
+    fn timers_are_placed_on_the_right_level_at_each_boundary() {
+        let max = TimingWheelHierarchy::<u64>::MAX_INTERVAL;
+        let boundaries = [
+            (0, (0, 0)),
+            (63, (0, 63)),
+            (64, (1, 1)),
+            (4095, (1, 63)),
+            (4096, (2, 1)),
+            (262_143, (2, 63)),
+            (262_144, (3, 1)),
+            (max, (3, 63)),
+        ];
+        let mut wheel = TimingWheelHierarchy::default();
+        for (id, &(after, expected)) in boundaries.iter().enumerate() {
+            wheel.schedule(after, id as u64);
+            assert_eq!(
+                position(&wheel, id as u64),
+                Some(expected),
+                "after {}",
+                after
+            );
+        }
+
+        // Away from zero, the level is decided by the digits the target shares with the clock.
+        let mut wheel = TimingWheelHierarchy {
+            elapsed: 100,
+            ..Default::default()
+        };
+        wheel.schedule(27, 0);
+        wheel.schedule(28, 1);
+        wheel.schedule(4096 - 100, 2);
+        assert_eq!(position(&wheel, 0), Some((0, 63)));
+        assert_eq!(position(&wheel, 1), Some((1, 2)));
+        assert_eq!(position(&wheel, 2), Some((2, 1)));
+    }
+
+    fn next_turns(app: &mut App, turns: usize) {
+        let mut events = app.world.get_resource_mut::<Events<NextTurn>>().unwrap();
+        for _ in 0..turns {
+            events.send(NextTurn);
+        }
+        app.update();
+    }
+
+    #[test]
+    fn turn_timers_only_advance_on_next_turn() {
+        let mut app = App::new();
+        app.add_plugin(TurnTimerPlugin);
+        let fired = log();
+        timers(&mut app).after(0, push_tick(&fired));
+        timers(&mut app).after(2, push_tick(&fired));
+        timers(&mut app).after(4, push_tick(&fired));
+        update(&mut app, 5);
+        assert!(logged(&fired).is_empty());
+        next_turns(&mut app, 1);
+        assert_eq!(logged(&fired), [0]);
+        next_turns(&mut app, 1);
+        update(&mut app, 3);
+        assert_eq!(logged(&fired), [0]);
+        next_turns(&mut app, 1);
+        assert_eq!(logged(&fired), [0, 2]);
+        next_turns(&mut app, 2);
+        assert_eq!(logged(&fired), [0, 2, 4]);
+        assert_eq!(timers(&mut app).elapsed_ticks(), 5);
+    }
+
+    #[test]
+    fn after_keyed_ignores_timers_for_a_pending_key() {
+        let mut app = app();
+        let fired = log();
+        let first = timers(&mut app).after_keyed(1, 3, push(&fired, "first"));
+        let second = timers(&mut app).after_keyed(1, 1, push(&fired, "second"));
+        timers(&mut app).after_keyed(2, 1, push(&fired, "other"));
+        assert_eq!(first, second);
+        update(&mut app, 5);
+        assert_eq!(logged(&fired), ["other", "first"]);
+        // Once the timer has fired the key is free again.
+        timers(&mut app).after_keyed(1, 0, push(&fired, "third"));
+        update(&mut app, 1);
+        assert_eq!(logged(&fired), ["other", "first", "third"]);
+    }
+
+    #[test]
+    fn after_keyed_replaces_timers_for_a_pending_key() {
+        let mut app = app();
+        let fired = log();
+        timers(&mut app).set_key_policy(KeyPolicy::Replace);
+        let first = timers(&mut app).after_keyed(1, 3, push(&fired, "first"));
+        let second = timers(&mut app).after_keyed(1, 1, push(&fired, "second"));
+        assert_ne!(first, second);
+        assert!(!timers(&mut app).cancel(first));
+        update(&mut app, 5);
+        assert_eq!(logged(&fired), ["second"]);
+        assert_eq!(timers(&mut app).total_cancelled(), 1);
+    }
+
+    #[test]
+    fn level_wrap_callbacks_fire_every_period() {
+        let mut app = app();
+        let wraps = log();
+        for level in 0..2 {
+            let wraps = wraps.clone();
+            timers(&mut app).on_level_wrap(level, move |world| {
+                let elapsed = get_timers(world).elapsed_ticks();
+                wraps.lock().unwrap().push((level, elapsed));
+            });
+        }
+        update(&mut app, 4096);
+        let mut expected: Vec<_> = (1..=64).map(|wrap| (0, wrap * 64)).collect();
+        expected.push((1, 4096));
+        assert_eq!(logged(&wraps), expected);
+    }
+
+    #[test]
+    fn simulate_matches_sorted_delays() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(110);
+        for case in 0..200 {
+            let longest = if case % 10 == 0 { 300_000 } else { 5000 };
+            let schedule: Vec<_> = (0..rng.gen_range(1..50))
+                .map(|id| (rng.gen_range(0..longest), id))
+                .collect();
+            let mut expected: Vec<_> = schedule
+                .iter()
+                .map(|&(delay, id)| (delay as u64, id))
+                .collect();
+            // A stable sort keeps timers firing on the same tick in the order scheduled.
+            expected.sort_by_key(|&(tick, _)| tick);
+            let total_ticks = expected.last().unwrap().0 as usize + 1;
+            assert_eq!(
+                Timers::simulate(&schedule, total_ticks),
+                expected,
+                "{:?}",
+                schedule
+            );
+        }
+    }
+
+    #[test]
+    fn stages_can_be_ordered_against_the_timer_stage() {
+        let mut app = app();
+        let fired = log();
+        let before = fired.clone();
+        app.add_stage_before(
+            TimerStage,
+            "before_timers",
+            SystemStage::single(move || before.lock().unwrap().push("before")),
+        );
+        timers(&mut app).after(0, push(&fired, "timer"));
+        app.update();
+        assert_eq!(logged(&fired), ["before", "timer"]);
+    }
+
+    #[test]
+    fn capped_repeaters_stop_after_max_repeats() {
+        let mut app = app();
+        let fired = log();
+        timers(&mut app).set_max_repeats(Some(3));
+        timers(&mut app).every(2, push_ticks(&fired));
+        let warnings = captured(Level::WARN, || update(&mut app, 20));
+        assert_eq!(logged(&fired), [2, 4, 6]);
+        assert_eq!(warnings.len(), 1);
+        assert!(timers(&mut app).is_empty());
+        assert_eq!(timers(&mut app).total_cancelled(), 1);
+    }
+
+    #[test]
+    fn fire_now_runs_a_pending_timer_immediately() {
+        let mut app = app();
+        let fired = log();
+        let handle = timers(&mut app).after(100, push(&fired, "cooldown"));
+        assert!(Timers::fire_now(&mut app.world, handle));
+        assert_eq!(logged(&fired), ["cooldown"]);
+        assert!(!Timers::fire_now(&mut app.world, handle));
+        update(&mut app, 101);
+        assert_eq!(logged(&fired), ["cooldown"]);
+        assert_eq!(timers(&mut app).total_fired(), 1);
+    }
+
+    #[test]
+    fn fire_now_works_from_within_a_timer() {
+        let mut app = app();
+        let fired = log();
+        let later = timers(&mut app).after(100, push(&fired, "later"));
+        let skip = push(&fired, "skip");
+        timers(&mut app).after(1, move |world| {
+            skip(world);
+            assert!(Timers::fire_now(world, later));
+        });
+        update(&mut app, 2);
+        assert_eq!(logged(&fired), ["skip", "later"]);
+    }
+
+    #[test]
+    fn schedule_sequence_fires_each_step_on_its_delay() {
+        let mut app = app();
+        let fired = log();
+        let steps = fired.clone();
+        let handles = timers(&mut app).schedule_sequence([0, 10, 25, 60], |step| {
+            let steps = steps.clone();
+            Box::new(move |world: &mut World| steps.lock().unwrap().push((step, fired_on(world))))
+        });
+        assert_eq!(handles.len(), 4);
+        update(&mut app, 61);
+        assert_eq!(logged(&fired), [(0, 0), (1, 10), (2, 25), (3, 60)]);
+    }
+
+    #[test]
+    fn timers_sent_from_another_thread_fire() {
+        let mut app = app();
+        let fired = log();
+        update(&mut app, 5);
+        let sender = timers(&mut app).sender();
+        let timer = push_tick(&fired);
+        let sent = thread::spawn(move || sender.after(2, timer))
+            .join()
+            .unwrap();
+        assert!(sent);
+        update(&mut app, 2);
+        assert!(logged(&fired).is_empty());
+        update(&mut app, 1);
+        assert_eq!(logged(&fired), [7]);
+    }
+
+    #[test]
+    fn sending_fails_once_the_timers_are_gone() {
+        let sender = Timers::default().sender();
+        assert!(!sender.after(0, |_| {}));
+    }
+
+    #[test]
+    fn fires_next_tick_looks_at_the_next_tick_only() {
+        let mut app = app();
+        assert!(!timers(&mut app).fires_next_tick());
+        timers(&mut app).after(1, |_| {});
+        assert!(!timers(&mut app).fires_next_tick());
+        update(&mut app, 1);
+        assert!(timers(&mut app).fires_next_tick());
+        update(&mut app, 1);
+        assert!(!timers(&mut app).fires_next_tick());
+    }
+
+    #[test]
+    fn fires_next_tick_accounts_for_cascades() {
+        let mut app = app();
+        timers(&mut app).after(4096, |_| {});
+        update(&mut app, 4095);
+        assert!(!timers(&mut app).fires_next_tick());
+        update(&mut app, 1);
+        // The timer is still on level 2 until the next tick cascades it down.
+        assert_eq!(timers(&mut app).level_occupancy(), [0, 0, 1, 0]);
+        assert!(timers(&mut app).fires_next_tick());
+    }
+
+    #[test]
+    fn fires_next_tick_includes_realtime_timers() {
+        let mut app = app();
+        timers(&mut app).after_realtime(0, |_| {});
+        assert!(timers(&mut app).fires_next_tick());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "timers must be run on the thread that built the app")]
+    fn running_timers_off_the_app_thread_panics() {
+        let other = thread::spawn(|| thread::current().id()).join().unwrap();
+        debug_assert_main_thread(other);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn after_ranged_is_reproducible_with_a_seeded_rng() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let delays = |seed| {
+            let mut timers = Timers::default();
+            let mut rng = StdRng::seed_from_u64(seed);
+            let handles: Vec<_> = (0..20)
+                .map(|_| timers.after_ranged(5, 10, &mut rng, |_| {}))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| timers.remaining(handle).unwrap())
+                .collect::<Vec<_>>()
+        };
+        let first = delays(118);
+        assert_eq!(first, delays(118));
+        assert!(first.iter().all(|delay| (5..=10).contains(delay)));
+        assert!(first.iter().any(|&delay| delay != first[0]));
+    }
+
+    #[test]
+    fn timers_fire_on_their_target_at_each_boundary() {
+        let delays = [0, 1, 63, 64, 65, 4095, 4096, 4097, 262_143, 262_144];
+        let mut wheel = TimingWheelHierarchy::default();
+        for &after in &delays {
+            wheel.schedule(after, after as u64);
+        }
+        let mut fired = Vec::new();
+        for _ in 0..=262_144 {
+            let tick = wheel.elapsed;
+            fired.extend(wheel.tick().into_iter().map(|(_, id)| (tick, id)));
+        }
+        let expected: Vec<_> = delays
+            .iter()
+            .map(|&after| (after as u64, after as u64))
+            .collect();
+        assert_eq!(fired, expected);
+    }
+
+    #[test]
+    fn timers_landing_on_cascade_ticks_fire_on_time() {
+        let mut app = app();
+        let fired = log();
+        for target in [4095u64, 4096, 4097, 8192, 262_143, 262_144, 262_145] {
+            for start in [target.saturating_sub(5000), target - 64, target - 1] {
+                let mut timers = timers(&mut app);
+                timers.set_elapsed_ticks(start);
+                timers.after((target - start) as usize, push_tick(&fired));
+                let ticks = target - start + 1;
+                for _ in 0..ticks {
+                    run_tick(&mut app.world);
+                }
+                assert_eq!(logged(&fired), [target], "from {}", start);
+                fired.lock().unwrap().clear();
+            }
+        }
+    }
+
+    #[test]
+    fn timers_scheduled_on_a_cascade_tick_fire_on_time() {
+        let mut app = app();
+        let fired = log();
+        timers(&mut app).set_elapsed_ticks(4090);
+        let again = fired.clone();
+        // This runs on tick 4095 and schedules timers for tick 4096, on which the cascade
+        // of level 1 runs, and for the ticks after it.
+        timers(&mut app).after(5, move |world| {
+            let mut timers = get_timers_mut(world);
+            timers.after(0, push_tick(&again));
+            timers.after(1, push_tick(&again));
+            timers.after(4096, push_tick(&again));
+        });
+        update(&mut app, 100);
+        assert_eq!(logged(&fired), [4096, 4097]);
+        update(&mut app, 4100);
+        assert_eq!(logged(&fired), [4096, 4097, 8192]);
+    }
+
+    #[derive(Component, Default)]
+    struct Health(u32);
+
+    #[test]
+    fn component_timers_only_run_while_the_component_exists() {
+        let mut app = app();
+        let healthy = app.world.spawn().insert(Health(10)).id();
+        let wounded = app.world.spawn().insert(Health(10)).id();
+        let fired = log();
+        for entity in [healthy, wounded] {
+            let fired = fired.clone();
+            timers(&mut app).after_component(2, entity, move |entity, mut health: Mut<Health>| {
+                health.0 += 1;
+                fired.lock().unwrap().push(entity);
+            });
+        }
+        update(&mut app, 1);
+        app.world.entity_mut(wounded).remove::<Health>();
+        update(&mut app, 2);
+        assert_eq!(logged(&fired), [healthy]);
+        assert_eq!(app.world.get::<Health>(healthy).unwrap().0, 11);
+    }
+
+    #[test]
+    fn phases_run_in_order_within_a_tick() {
+        let mut app = app();
+        let fired = log();
+        let mut timers = timers(&mut app);
+        timers.after_phased(1, Phase::Late, push(&fired, "late 1"));
+        timers.after(1, push(&fired, "normal 1"));
+        timers.after_phased(1, Phase::Early, push(&fired, "early 1"));
+        timers.after_phased(1, Phase::Late, push(&fired, "late 2"));
+        timers.after_phased(1, Phase::Normal, push(&fired, "normal 2"));
+        timers.after_phased(1, Phase::Early, push(&fired, "early 2"));
+        update(&mut app, 2);
+        assert_eq!(
+            logged(&fired),
+            ["early 1", "early 2", "normal 1", "normal 2", "late 1", "late 2"]
+        );
+    }
+
+    /// Return the lifetime counters of the timers along with the number pending.
+    fn totals(timers: &Timers) -> (u64, u64, u64, usize) {
+        (
+            timers.total_scheduled(),
+            timers.total_fired(),
+            timers.total_cancelled(),
+            timers.len(),
+        )
+    }
+
+    #[test]
+    fn lifetime_counters_balance_the_pending_count() {
+        let mut app = app();
+        let mut timers = timers(&mut app);
+        let cancelled = timers.after(5, |_| {});
+        timers.after(1, |_| {});
+        timers.after(10, |_| {});
+        timers.every(3, |_| {});
+        assert_eq!(totals(&timers), (4, 0, 0, 4));
+        assert!(timers.cancel(cancelled));
+        assert!(!timers.cancel(cancelled));
+        assert_eq!(totals(&timers), (4, 0, 1, 3));
+        update(&mut app, 7);
+        // The repeating timer has fired twice, but only finishes once it is cancelled.
+        assert_eq!(totals(get_timers(&app.world)), (4, 1, 1, 2));
+        assert_eq!(get_timers(&app.world).fires(), 3);
+        get_timers_mut(&mut app.world).cancel_all_repeating();
+        update(&mut app, 5);
+        assert_eq!(totals(get_timers(&app.world)), (4, 2, 2, 0));
+    }
+
+    #[test]
+    fn diverging_counters_are_logged() {
+        let mut app = app();
+        timers(&mut app).after(5, |_| {});
+        timers(&mut app).total_scheduled += 1;
+        let errors = captured(Level::ERROR, || update(&mut app, 1));
+        assert_eq!(
+            errors,
+            ["timer accounting diverged: 2 scheduled, 0 fired, 0 cancelled, 1 pending"]
+        );
+    }
+
+    #[test]
+    fn named_timers_call_their_handler_with_the_params() {
+        #[derive(Default)]
+        struct Said(Vec<String>);
+
+        fn say(world: &mut World, params: &TimerParams) {
+            let message = params.get("message").expect("Failed");
+            world
+                .get_resource_mut::<Said>()
+                .expect("Failed")
+                .0
+                .push(message);
+        }
+
+        let mut app = app();
+        app.world.insert_resource(Said::default());
+        app.world
+            .resource_scope(|world, mut registry: Mut<HandlerRegistry>| {
+                registry.register_or_panic("say", say);
+                let mut timers = get_timers_mut(world);
+                let params = TimerParams([("message".to_string(), "hello".to_string())].into());
+                assert!(timers.after_named(&registry, 2, "say", params).is_some());
+                let unknown = timers.after_named(&registry, 2, "shout", TimerParams::default());
+                assert!(unknown.is_none());
+            });
+        update(&mut app, 3);
+        assert_eq!(
+            app.world.get_resource::<Said>().expect("Failed").0,
+            ["hello"]
+        );
+    }
+
+    #[test]
+    fn cancel_all_repeating_leaves_one_shot_timers() {
+        let mut app = app();
+        let fired = log();
+        timers(&mut app).every(1, push_ticks(&fired));
+        timers(&mut app).start_now_then_every(2, push_ticks(&fired));
+        timers(&mut app).after(3, push_tick(&fired));
+        timers(&mut app).after_read(3, |_, _| {});
+        update(&mut app, 2);
+        assert_eq!(timers(&mut app).cancel_all_repeating(), 2);
+        assert_eq!(timers(&mut app).len(), 2);
+        fired.lock().unwrap().clear();
+        update(&mut app, 5);
+        assert_eq!(logged(&fired), [3]);
+        assert!(timers(&mut app).is_empty());
+    }
+
+    #[test]
+    fn now_waits_for_the_next_tick() {
+        let mut app = app();
+        let fired = log();
+        timers(&mut app).now(push(&fired, "now"));
+        assert!(logged(&fired).is_empty());
+        update(&mut app, 1);
+        assert_eq!(logged(&fired), ["now"]);
+    }
+
+    #[test]
+    fn now_immediate_runs_before_returning() {
+        let mut app = app();
+        let fired = log();
+        Timers::now_immediate(&mut app.world, push(&fired, "immediate"));
+        assert_eq!(logged(&fired), ["immediate"]);
+        let timers = get_timers(&app.world);
+        assert_eq!((timers.total_scheduled(), timers.total_fired()), (1, 1));
+        assert!(timers.is_empty());
+    }
+
+    #[test]
+    fn drain_budgeted_returns_the_timers_over_budget() {
+        let mut app = app();
+        let fired = log();
+        for name in ["a", "b", "c", "d"] {
+            timers(&mut app).after(0, push(&fired, name));
+        }
+        let deferred = Timers::drain_budgeted(&mut app.world, Some(2));
+        assert_eq!(logged(&fired), ["a", "b"]);
+        assert_eq!(deferred.len(), 2);
+        for timer in deferred {
+            timer(&mut app.world);
+        }
+        assert_eq!(logged(&fired), ["a", "b", "c", "d"]);
+        assert!(timers(&mut app).is_empty());
+        assert!(Timers::drain_budgeted(&mut app.world, None).is_empty());
+    }
+
+    #[test]
+    fn drain_budgeted_does_not_spend_budget_on_cancelled_timers() {
+        let mut app = app();
+        let fired = log();
+        let b = Arc::new(Mutex::new(None));
+        let cancel = b.clone();
+        let a = push(&fired, "a");
+        timers(&mut app).after(0, move |world| {
+            a(world);
+            let b = cancel.lock().unwrap().unwrap();
+            get_timers_mut(world).cancel(b);
+        });
+        *b.lock().unwrap() = Some(timers(&mut app).after(0, push(&fired, "b")));
+        timers(&mut app).after(0, push(&fired, "c"));
+        let deferred = Timers::drain_budgeted(&mut app.world, Some(2));
+        assert!(deferred.is_empty());
+        assert_eq!(logged(&fired), ["a", "c"]);
+    }
+
+    #[test]
+    fn drain_budgeted_moves_repeaters_over_budget_to_the_next_tick() {
+        let mut app = app();
+        let fired = log();
+        timers(&mut app).set_fire_log(Some(10));
+        timers(&mut app).after(0, |_| {});
+        timers(&mut app).every(5, push_ticks(&fired));
+        timers(&mut app).set_elapsed_ticks(5);
+        let deferred = Timers::drain_budgeted(&mut app.world, Some(1));
+        assert!(deferred.is_empty());
+        assert!(logged(&fired).is_empty());
+        assert_eq!(timers(&mut app).fires(), 1);
+        assert!(Timers::drain_budgeted(&mut app.world, Some(1)).is_empty());
+        assert_eq!(logged(&fired), [6]);
+        let timers = get_timers(&app.world);
+        assert_eq!(timers.fires(), 2);
+        assert_eq!(timers.fire_log().len(), 2);
+        // The repeater keeps its cadence from the tick it actually ran on.
+        assert_eq!(timers.pending_sorted()[0].1, 11);
+    }
+
+    #[test]
+    fn debounce_fires_once_after_the_last_call() {
+        let mut app = app();
+        let fired = log();
+        for _ in 0..5 {
+            for _ in 0..3 {
+                timers(&mut app).debounce(1, 10, push_tick(&fired));
+            }
+            update(&mut app, 2);
+        }
+        assert_eq!(timers(&mut app).len(), 1);
+        update(&mut app, 20);
+        // The last call was made on tick 8, so it fires ten ticks later.
+        assert_eq!(logged(&fired), [18]);
+        assert_eq!(timers(&mut app).total_cancelled(), 14);
+    }
+
+    #[test]
+    fn failing_result_timers_log_their_error() {
+        let mut app = app();
+        timers(&mut app).after_result(1, |_| Err("out of mana"));
+        timers(&mut app).after_result(1, |_| Ok::<(), ()>(()));
+        let errors = captured(Level::ERROR, || update(&mut app, 2));
+        assert_eq!(errors, ["timer failed: \"out of mana\""]);
+        assert_eq!(timers(&mut app).total_fired(), 2);
+    }
+
+    #[test]
+    fn max_interval_matches_the_depth_of_the_wheel() {
+        let mut timers = Timers::default();
+        assert_eq!(timers.levels(), 4);
+        assert_eq!(timers.max_interval(), 64u64.pow(4) - 1);
+        let max = timers.max_interval() as usize;
+        assert!(timers.try_after(max, |_| {}).is_ok());
+        assert!(timers.try_after(max + 1, |_| {}).is_err());
+    }
+
+    #[test]
+    fn retagged_timers_are_cancelled_with_their_new_tag() {
+        let mut app = app();
+        let fired = log();
+        let projectile = timers(&mut app).after_tagged(5, 1, push(&fired, "projectile"));
+        timers(&mut app).after_tagged(5, 1, push(&fired, "old owner"));
+        assert!(timers(&mut app).retag(projectile, Some(2)));
+        assert_eq!(timers(&mut app).cancel_tag(1), 1);
+        assert_eq!(timers(&mut app).count_tag(2), 1);
+        assert_eq!(timers(&mut app).cancel_tag(2), 1);
+        assert!(!timers(&mut app).retag(projectile, None));
+        update(&mut app, 6);
+        assert!(logged(&fired).is_empty());
+    }
+
+    #[test]
+    fn same_interval_repeaters_keep_their_order() {
+        for interval in [7, 100] {
+            let mut app = app();
+            let fired = log();
+            for name in ["a", "b", "c"] {
+                let fired = fired.clone();
+                timers(&mut app).every(interval, move |_| fired.lock().unwrap().push(name));
+            }
+            update(&mut app, 10 * interval + 1);
+            let expected: Vec<_> = (0..10).flat_map(|_| ["a", "b", "c"]).collect();
+            assert_eq!(logged(&fired), expected);
+        }
+    }
+
+    #[test]
+    fn first_tick_callbacks_run_once() {
+        let mut app = app();
+        let fired = log();
+        timers(&mut app).pause();
+        let first = fired.clone();
+        timers(&mut app).on_first_tick(move |world| {
+            first
+                .lock()
+                .unwrap()
+                .push(get_timers(world).elapsed_ticks())
+        });
+        update(&mut app, 3);
+        assert!(logged(&fired).is_empty());
+        timers(&mut app).resume();
+        update(&mut app, 10);
+        assert_eq!(logged(&fired), [0]);
+    }
+
+    #[test]
+    fn after_timer_chains_dependent_timers() {
+        let mut app = app();
+        let fired = log();
+        let first = timers(&mut app).after(3, push_tick(&fired));
+        let second = timers(&mut app)
+            .after_timer(first, 2, push_tick(&fired))
+            .unwrap();
+        let third = timers(&mut app).after_timer(second, 4, push_tick(&fired));
+        assert!(third.is_some());
+        update(&mut app, 10);
+        assert_eq!(logged(&fired), [3, 5, 9]);
+        assert!(timers(&mut app).after_timer(first, 1, |_| {}).is_none());
+    }
+
+    #[test]
+    fn after_timer_accepts_a_base_timer_due_this_tick() {
+        let mut app = app();
+        let fired = log();
+        let chained = fired.clone();
+        let base = Arc::new(Mutex::new(None));
+        let due = base.clone();
+        timers(&mut app).after(2, move |world| {
+            let base = due.lock().unwrap().unwrap();
+            let mut timers = get_timers_mut(world);
+            timers.after_timer(base, 0, push_tick(&chained)).unwrap();
+            timers.after_timer(base, 3, push_tick(&chained)).unwrap();
+        });
+        *base.lock().unwrap() = Some(timers(&mut app).after(2, push_tick(&fired)));
+        assert_eq!(Timers::run_to_next(&mut app.world), Some(3));
+        update(&mut app, 5);
+        assert_eq!(logged(&fired), [2, 3, 5]);
+    }
+
+    /// Add a system to the stage that pushes the name to the log every update.
+    fn add_marker<L: StageLabel>(
+        app: &mut App,
+        stage: L,
+        fired: &Log<&'static str>,
+        name: &'static str,
+    ) {
+        let fired = fired.clone();
+        app.add_system_to_stage(stage, move || fired.lock().unwrap().push(name));
+    }
+
+    /// Return the order in which a timer firing on the next tick and a system in the update
+    /// stage run.
+    fn order_after_update(mut app: App, fired: &Log<&'static str>) -> Vec<&'static str> {
+        add_marker(&mut app, CoreStage::Update, fired, "update");
+        timers(&mut app).after(0, push(fired, "timer"));
+        app.update();
+        logged(fired)
+    }
+
+    #[test]
+    fn timers_run_in_the_stage_they_are_placed_after() {
+        let default = log();
+        assert_eq!(order_after_update(app(), &default), ["update", "timer"]);
+
+        let first = log();
+        let mut app = App::new();
+        app.add_plugin(TimerPlugin::run_in(CoreStage::First));
+        assert_eq!(order_after_update(app, &first), ["timer", "update"]);
+    }
+
+    #[test]
+    fn cancelling_among_many_timers_removes_exactly_those() {
+        let mut app = app();
+        let fired = log();
+        let handles: Vec<_> = (0..10_000)
+            .map(|i| {
+                let fired = fired.clone();
+                let delay = i * 7 % 9000;
+                let handle = timers(&mut app).after(delay, move |_| fired.lock().unwrap().push(i));
+                (i, delay, handle)
+            })
+            .collect();
+        let mut timers = timers(&mut app);
+        for &(i, _, handle) in &handles {
+            if i % 3 == 0 {
+                assert!(timers.cancel(handle));
+            }
+        }
+        // Lookups keep working as the timers cascade down the wheel.
+        for _ in 0..4100 {
+            run_tick(&mut app.world);
+        }
+        let timers = get_timers(&app.world);
+        for &(i, delay, handle) in &handles {
+            let expected = (i % 3 != 0 && delay >= 4100).then(|| delay - 4100);
+            assert_eq!(timers.remaining(handle), expected);
+        }
+        update(&mut app, 5000);
+        let mut fired = logged(&fired);
+        fired.sort_unstable();
+        let expected: Vec<_> = (0..10_000).filter(|i| i % 3 != 0).collect();
+        assert_eq!(fired, expected);
+        assert!(get_timers(&app.world).targets.is_empty());
+    }
+
+    #[test]
+    fn flush_runs_every_remaining_timer_in_order() {
+        let mut app = app();
+        let fired = log();
+        timers(&mut app).after(30, push(&fired, "c"));
+        timers(&mut app).after(2, push(&fired, "a"));
+        timers(&mut app).after(5000, push(&fired, "d"));
+        timers(&mut app).every(1, |_| {});
+        let chained = push(&fired, "chained");
+        timers(&mut app).after(10, move |world| {
+            get_timers_mut(world).after(100, chained);
+        });
+        timers(&mut app).after_phased(2, Phase::Late, push(&fired, "b"));
+        timers(&mut app).pause();
+        Timers::flush(&mut app.world);
+        assert_eq!(logged(&fired), ["a", "b", "c", "chained", "d"]);
+        assert!(timers(&mut app).is_empty());
+        assert_eq!(timers(&mut app).elapsed_ticks(), 5001);
+    }
+
+    #[test]
+    fn flush_runs_realtime_timers() {
+        let mut app = app();
+        let fired = log();
+        timers(&mut app).set_time_scale(0.0);
+        timers(&mut app).after_realtime(3, push(&fired, "realtime"));
+        timers(&mut app).after(1, push(&fired, "scaled"));
+        Timers::flush(&mut app.world);
+        assert_eq!(logged(&fired), ["scaled", "realtime"]);
+        assert!(timers(&mut app).is_empty());
+    }
+
+    #[test]
+    fn snapshot_counts_bins_timers_by_ticks_left() {
+        let mut app = app();
+        let mut timers = timers(&mut app);
+        for after in [0, 2, 2, 4, 100] {
+            timers.after(after, |_| {});
+        }
+        timers.every(3, |_| {});
+        assert_eq!(timers.snapshot_counts(5), [1, 0, 2, 1, 1]);
+        update(&mut app, 1);
+        assert_eq!(get_timers(&app.world).snapshot_counts(3), [0, 2, 1]);
+        assert!(get_timers(&app.world).snapshot_counts(0).is_empty());
+    }
+
+    #[test]
+    fn delays_convert_into_ticks() {
+        assert_eq!(5usize.into_ticks(60.0), 5);
+        assert_eq!(5u32.into_ticks(60.0), 5);
+        assert_eq!(5u64.into_ticks(60.0), 5);
+        assert_eq!(5i32.into_ticks(60.0), 5);
+        assert_eq!(Duration::from_secs(2).into_ticks(60.0), 120);
+        assert_eq!(Duration::from_millis(25).into_ticks(60.0), 2);
+        assert_eq!(Duration::from_millis(24).into_ticks(30.0), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "timer delay must not be negative")]
+    fn negative_delays_are_rejected() {
+        (-1).into_ticks(60.0);
+    }
+
+    #[test]
+    fn durations_use_the_tick_rate() {
+        let mut app = app();
+        let fired = log();
+        timers(&mut app).set_tick_rate(10.0);
+        assert_eq!(timers(&mut app).tick_rate(), 10.0);
+        timers(&mut app).after(Duration::from_millis(500), push_tick(&fired));
+        timers(&mut app).after(3u32, push_tick(&fired));
+        timers(&mut app).after(4u64, push_tick(&fired));
+        update(&mut app, 6);
+        assert_eq!(logged(&fired), [3, 4, 5]);
+    }
+
+    #[test]
+    fn timers_scheduled_while_paused_wait_for_resume() {
+        let mut app = app();
+        let fired = log();
+        timers(&mut app).pause();
+        assert!(timers(&mut app).is_paused());
+        assert!(timers(&mut app).try_after(0, push_tick(&fired)).is_ok());
+        update(&mut app, 5);
+        assert!(logged(&fired).is_empty());
+        assert_eq!(timers(&mut app).elapsed_ticks(), 0);
+        timers(&mut app).resume();
+        update(&mut app, 1);
+        assert_eq!(logged(&fired), [0]);
+    }
+
+    #[test]
+    fn scheduling_while_paused_can_be_disallowed() {
+        let mut timers = Timers::default();
+        timers.set_schedule_while_paused(false);
+        assert!(timers.try_after(0, |_| {}).is_ok());
+        timers.pause();
+        assert_eq!(timers.try_after(0, |_| {}).err(), Some(TimerError::Paused));
+        timers.resume();
+        assert!(timers.try_after(0, |_| {}).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot schedule a timer while paused")]
+    fn after_panics_while_paused_if_disallowed() {
+        let mut timers = Timers::default();
+        timers.set_schedule_while_paused(false);
+        timers.pause();
+        timers.after(0, |_| {});
+    }
+
+    #[test]
+    fn timers_cancelled_earlier_in_their_batch_do_not_run() {
+        let mut app = app();
+        let fired = log();
+        let b = Arc::new(Mutex::new(None));
+        let cancel = b.clone();
+        let a = push(&fired, "a");
+        timers(&mut app).after(1, move |world| {
+            a(world);
+            let b = cancel.lock().unwrap().unwrap();
+            assert!(get_timers_mut(world).cancel(b));
+            assert!(!get_timers_mut(world).cancel(b));
+        });
+        *b.lock().unwrap() = Some(timers(&mut app).after(1, push(&fired, "b")));
+        timers(&mut app).after(1, push(&fired, "c"));
+        update(&mut app, 3);
+        assert_eq!(logged(&fired), ["a", "c"]);
+        let timers = get_timers(&app.world);
+        assert_eq!((timers.total_fired(), timers.total_cancelled()), (2, 1));
+        assert!(timers.is_empty());
+    }
+
+    struct Score(u32);
+
+    #[test]
+    fn resource_timers_are_skipped_without_the_resource() {
+        let mut app = app();
+        app.world.insert_resource(Score(0));
+        timers(&mut app).after_resource(1, |_, mut score: Mut<Score>| score.0 += 1);
+        update(&mut app, 2);
+        assert_eq!(app.world.get_resource::<Score>().unwrap().0, 1);
+
+        let fired = log();
+        let ran = fired.clone();
+        timers(&mut app).after_resource(1, move |_, _: Mut<Score>| ran.lock().unwrap().push(()));
+        app.world.remove_resource::<Score>();
+        update(&mut app, 2);
+        assert!(logged(&fired).is_empty());
+        assert!(timers(&mut app).is_empty());
+    }
+
+    #[cfg(feature = "single_thread")]
+    #[test]
+    fn timers_need_not_be_send_with_single_thread() {
+        let mut app = app();
+        assert!(app.world.get_non_send_resource::<Timers>().is_some());
+        let fired = Rc::new(Cell::new(0));
+        let once = fired.clone();
+        let every = fired.clone();
+        let mut timers = app.world.get_non_send_resource_mut::<Timers>().unwrap();
+        timers.after(1, move |_| once.set(once.get() + 1));
+        timers.every(1, move |_| every.set(every.get() + 10));
+        update(&mut app, 3);
+        assert_eq!(fired.get(), 21);
+    }
+
+    #[test]
+    fn scheduled_between_lists_timers_in_the_window() {
+        let mut timers = Timers::default();
+        let handles: Vec<_> = [9, 2, 5, 10, 1, 5]
+            .into_iter()
+            .map(|after| timers.after(after, |_| {}))
+            .collect();
+        assert_eq!(
+            timers.scheduled_between(2, 10),
+            [
+                (handles[1], 2),
+                (handles[2], 5),
+                (handles[5], 5),
+                (handles[0], 9)
+            ]
+        );
+        assert!(timers.scheduled_between(6, 9).is_empty());
+        assert!(timers.scheduled_between(5, 5).is_empty());
+    }
+
+    #[test]
+    fn fire_log_is_identical_across_runs() {
+        let run = || {
+            let mut app = app();
+            let mut timers = timers(&mut app);
+            timers.set_fire_log(Some(3));
+            timers.every(2, |_| {});
+            timers.after_tagged(3, 7, |world| {
+                get_timers_mut(world).after(0, |_| {});
+            });
+            update(&mut app, 6);
+            get_timers(&app.world).fire_log().to_vec()
+        };
+        let log = run();
+        assert_eq!(log, run());
+        assert_eq!(log.len(), 3);
+        assert_eq!(
+            log.iter()
+                .map(|record| (record.tick, record.tag))
+                .collect::<Vec<_>>(),
+            [(2, None), (3, Some(7)), (4, None)]
+        );
+        let mut app = app();
+        timers(&mut app).set_fire_log(Some(1));
+        timers(&mut app).after(0, |_| {});
+        update(&mut app, 1);
+        timers(&mut app).clear_log();
+        assert!(get_timers(&app.world).fire_log().is_empty());
+    }
+
+    #[test]
+    fn run_to_next_jumps_to_each_timer() {
+        let mut app = app();
+        let fired = log();
+        for after in [3, 100, 5000] {
+            timers(&mut app).after(after, push_tick(&fired));
+        }
+        assert_eq!(Timers::run_to_next(&mut app.world), Some(4));
+        assert_eq!(Timers::run_to_next(&mut app.world), Some(97));
+        assert_eq!(Timers::run_to_next(&mut app.world), Some(4900));
+        assert_eq!(logged(&fired), [3, 100, 5000]);
+        assert_eq!(Timers::run_to_next(&mut app.world), None);
+    }
+
+    #[test]
+    fn run_to_next_finds_realtime_timers() {
+        let mut app = app();
+        let fired = log();
+        timers(&mut app).after_realtime(6, push(&fired, "realtime"));
+        timers(&mut app).after(9, push(&fired, "scaled"));
+        assert_eq!(Timers::run_to_next(&mut app.world), Some(7));
+        assert_eq!(logged(&fired), ["realtime"]);
+        assert_eq!(Timers::run_to_next(&mut app.world), Some(3));
+        assert_eq!(logged(&fired), ["realtime", "scaled"]);
+        timers(&mut app).after_realtime(2, push(&fired, "alone"));
+        assert_eq!(get_timers(&app.world).len(), 1);
+        assert_eq!(Timers::run_to_next(&mut app.world), Some(3));
+        assert_eq!(logged(&fired).last(), Some(&"alone"));
+        assert_eq!(Timers::run_to_next(&mut app.world), None);
+    }
+
+    #[test]
+    fn after_in_runs_immediately_only_in_immediate_mode() {
+        let mut app = app();
+        let fired = log();
+        Timers::after_in(&mut app.world, 0, push(&fired, "deferred"));
+        assert!(logged(&fired).is_empty());
+        update(&mut app, 1);
+        assert_eq!(logged(&fired), ["deferred"]);
+        timers(&mut app).set_immediate_mode(true);
+        Timers::after_in(&mut app.world, 0, push(&fired, "immediate"));
+        assert_eq!(logged(&fired), ["deferred", "immediate"]);
+        Timers::after_in(&mut app.world, 1, push(&fired, "later"));
+        assert_eq!(logged(&fired).len(), 2);
+        // Inside the timer stage a zero tick timer still waits for the next tick.
+        let nested = fired.clone();
+        timers(&mut app).after(0, move |world| {
+            Timers::after_in(world, 0, push(&nested, "nested"));
+        });
+        update(&mut app, 1);
+        assert_eq!(logged(&fired), ["deferred", "immediate"]);
+        update(&mut app, 1);
+        assert_eq!(logged(&fired), ["deferred", "immediate", "later", "nested"]);
+    }
+
+    #[test]
+    fn for_each_pending_visits_every_timer() {
+        let mut timers = Timers::default();
+        let a = timers.after(300, |_| {});
+        let b = timers.after_tagged(4, 2, |_| {});
+        let c = timers.after(70_000, |_| {});
+        let mut visited = Vec::new();
+        timers.for_each_pending(|handle, target, tag| visited.push((handle, target, tag)));
+        visited.sort();
+        assert_eq!(
+            visited,
+            [(a, 300, None), (b, 4, Some(2)), (c, 70_000, None)]
+        );
+    }
+
+    #[derive(Component, Default)]
+    struct Visible;
+
+    #[test]
+    fn blink_toggles_the_component_count_times() {
+        let mut app = app();
+        let entity = app.world.spawn().id();
+        timers(&mut app).blink::<Visible>(entity, 3, 4);
+        let mut states = Vec::new();
+        for _ in 0..16 {
+            update(&mut app, 1);
+            states.push(app.world.entity(entity).contains::<Visible>());
+        }
+        let toggles = states.windows(2).filter(|pair| pair[0] != pair[1]).count();
+        assert_eq!(toggles, 4);
+        assert!(!states[2] && states[3] && !states[6] && states[9] && !states[12]);
+        assert!(get_timers(&app.world).is_empty());
+    }
+
+    #[test]
+    fn set_elapsed_ticks_keeps_absolute_targets() {
+        let mut app = app();
+        let fired = log();
+        for after in [10, 500, 1000] {
+            timers(&mut app).after(after, push_tick(&fired));
+        }
+        timers(&mut app).set_elapsed_ticks(498);
+        assert_eq!(get_timers(&app.world).elapsed_ticks(), 498);
+        update(&mut app, 1);
+        // The timer whose tick has passed fires on the next tick.
+        assert_eq!(logged(&fired), [498]);
+        update(&mut app, 2);
+        assert_eq!(logged(&fired), [498, 500]);
+        assert_eq!(get_timers(&app.world).pending_sorted()[0].1, 1000);
+    }
+
+    #[test]
+    fn after_realtime_ignores_the_time_scale() {
+        let mut app = app();
+        let fired = log();
+        timers(&mut app).set_time_scale(0.25);
+        timers(&mut app).after_realtime(4, push(&fired, "realtime"));
+        timers(&mut app).after(1, push(&fired, "scaled"));
+        update(&mut app, 5);
+        assert_eq!(logged(&fired), ["realtime"]);
+        assert_eq!(get_timers(&app.world).elapsed_ticks(), 1);
+        update(&mut app, 4);
+        assert_eq!(logged(&fired), ["realtime", "scaled"]);
+    }
+
+    #[test]
+    fn extend_schedules_each_timer() {
+        let mut app = app();
+        let fired = log();
+        timers(&mut app).extend((0..3).map(|i| {
+            let timer: BoxedSystem = Box::new(push(&fired, i));
+            (2 - i, timer)
+        }));
+        assert_eq!(get_timers(&app.world).len(), 3);
+        update(&mut app, 3);
+        assert_eq!(logged(&fired), [2, 1, 0]);
+    }
+
+    #[test]
+    fn unfocused_policies() {
+        let mut app = app();
+        let mut time = Time::default();
+        time.update();
+        std::thread::sleep(Duration::from_millis(50));
+        time.update();
+        app.world.insert_resource(time);
+        let mut timers = timers(&mut app);
+        timers.set_tick_rate(100.0);
+        timers.focused = false;
+        assert_eq!(update_ticks(&mut app.world), 1);
+        get_timers_mut(&mut app.world).set_unfocused_policy(UnfocusedPolicy::KeepRate);
+        assert!(update_ticks(&mut app.world) >= 5);
+        let mut timers = get_timers_mut(&mut app.world);
+        assert!(timers.is_clock_running());
+        timers.set_unfocused_policy(UnfocusedPolicy::Pause);
+        assert!(!timers.is_clock_running());
+        timers.focused = true;
+        assert!(timers.is_clock_running());
+        assert_eq!(update_ticks(&mut app.world), 1);
+        // Without a window the app counts as focused.
+        get_timers_mut(&mut app.world).after(0, |_| {});
+        update(&mut app, 1);
+        assert!(get_timers(&app.world).is_empty());
+    }
+
+    #[test]
+    fn throttle_fires_on_the_leading_edge() {
+        let mut app = app();
+        let fired = log();
+        let first = timers(&mut app).throttle(1, 5, push_tick(&fired));
+        assert_eq!(timers(&mut app).throttle(1, 5, push_tick(&fired)), first);
+        update(&mut app, 1);
+        assert_eq!(logged(&fired), [0]);
+        // Throttled until five ticks after the last fire.
+        timers(&mut app).throttle(1, 5, push_tick(&fired));
+        update(&mut app, 10);
+        assert_eq!(logged(&fired), [0, 5]);
+    }
+
+    #[test]
+    fn throttle_keys_are_separate_from_other_keys() {
+        let mut app = app();
+        let fired = log();
+        let debounced = timers(&mut app).debounce(1, 3, push(&fired, "debounce"));
+        let throttled = timers(&mut app).throttle(1, 5, push(&fired, "throttle"));
+        assert_ne!(throttled, debounced);
+        update(&mut app, 4);
+        assert_eq!(logged(&fired), ["throttle", "debounce"]);
+        // The debounced timer firing does not count as a throttled fire.
+        let mut timers = timers(&mut app);
+        let throttled = timers.throttle(1, 5, |_| {});
+        assert_eq!(timers.pending_sorted(), [(throttled, 5)]);
+        assert_ne!(timers.after_keyed(1, 1, |_| {}), throttled);
+        let mut timers = Timers::from_pending(timers.extract());
+        assert_eq!(timers.throttle(1, 5, |_| {}), throttled);
+    }
+
+    #[test]
+    fn wrap_pending_wraps_every_one_shot_timer() {
+        let mut app = app();
+        let fired = log();
+        let wrapped = Arc::new(Mutex::new(0));
+        let mut timers = timers(&mut app);
+        timers.after(1, push(&fired, "scaled"));
+        timers.after_realtime(1, push(&fired, "realtime"));
+        timers.after_tagged(1, 3, push(&fired, "frozen"));
+        timers.every(1, |_| {});
+        timers.pause_group(3);
+        let count = wrapped.clone();
+        timers.wrap_pending(move |system| {
+            let count = count.clone();
+            Box::new(move |world: &mut World| {
+                *count.lock().unwrap() += 1;
+                system(world);
+            })
+        });
+        update(&mut app, 2);
+        get_timers_mut(&mut app.world).resume_group(3);
+        update(&mut app, 2);
+        assert_eq!(logged(&fired), ["realtime", "scaled", "frozen"]);
+        assert_eq!(*wrapped.lock().unwrap(), 3);
+    }
+
+    #[test]
+    fn tick_budget_runs_early_timers_first() {
+        let mut app = app();
+        let fired = log();
+        let mut timers = timers(&mut app);
+        timers.set_tick_budget(Some(2));
+        timers.after_phased(0, Phase::Late, push(&fired, "late"));
+        timers.after(0, push(&fired, "normal"));
+        timers.after_phased(0, Phase::Early, push(&fired, "early"));
+        timers.after_phased(1, Phase::Early, push(&fired, "next early"));
+        update(&mut app, 1);
+        assert_eq!(logged(&fired), ["early", "normal"]);
+        update(&mut app, 1);
+        assert_eq!(logged(&fired), ["early", "normal", "next early", "late"]);
+    }
+
+    #[test]
+    fn start_now_then_every_fires_on_the_next_tick() {
+        let mut app = app();
+        let fired = log();
+        timers(&mut app).start_now_then_every(4, push_ticks(&fired));
+        update(&mut app, 10);
+        assert_eq!(logged(&fired), [0, 4, 8]);
+    }
+
+    #[test]
+    fn pending_by_tag_groups_tagged_timers() {
+        let mut timers = Timers::default();
+        let a = timers.after_tagged(9, 1, |_| {});
+        let b = timers.after_tagged(2, 2, |_| {});
+        timers.after(1, |_| {});
+        let c = timers.after_tagged(1, 1, |_| {});
+        let groups = timers.pending_by_tag();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[&1], [a, c]);
+        assert_eq!(groups[&2], [b]);
+    }
+
+    #[test]
+    fn replace_keyed_cancels_the_previous_timer() {
+        let mut app = app();
+        let fired = log();
+        let (replaced, old) = timers(&mut app).replace_keyed(1, 3, push(&fired, "old"));
+        assert_eq!(replaced, None);
+        let (replaced, new) = timers(&mut app).replace_keyed(1, 3, push(&fired, "new"));
+        assert_eq!(replaced, Some(old));
+        let timers = get_timers(&app.world);
+        assert!(!timers.is_pending(old));
+        assert!(timers.is_pending(new));
+        update(&mut app, 4);
+        assert_eq!(logged(&fired), ["new"]);
+    }
+
+    #[test]
+    fn read_only_timers_apply_their_commands_in_order() {
+        let mut app = app();
+        let fired = log();
+        app.world.insert_resource(Score(2));
+        for i in 0..3 {
+            let fired = fired.clone();
+            timers(&mut app).after_read(1, move |world: &World, commands: &mut Commands| {
+                let score = world.get_resource::<Score>().expect("Failed").0;
+                fired.lock().unwrap().push(score);
+                commands.insert_resource(Score(score * 10 + i));
+            });
+        }
+        let after = fired.clone();
+        timers(&mut app).after(1, move |world| {
+            after
+                .lock()
+                .unwrap()
+                .push(world.get_resource::<Score>().expect("Failed").0);
+        });
+        update(&mut app, 2);
+        // Every read-only timer sees the world from before any of their commands applied.
+        assert_eq!(logged(&fired), [2, 2, 2, 22]);
+    }
+
+    #[test]
+    fn after_transform_propagate_runs_timers_after_post_update() {
+        let fired = log();
+        let mut app = App::new();
+        app.add_plugin(TimerPlugin::after_transform_propagate());
+        add_marker(&mut app, CoreStage::PostUpdate, &fired, "post update");
+        add_marker(&mut app, CoreStage::Last, &fired, "last");
+        timers(&mut app).after(0, push(&fired, "timer"));
+        app.update();
+        assert_eq!(logged(&fired), ["post update", "timer", "last"]);
+    }
+
+    fn add_score(world: &mut World, _: &TimerParams) {
+        world.get_resource_mut::<Score>().expect("Failed").0 += 1;
+    }
+
+    #[test]
+    fn replay_reproduces_bulk_cancels() {
+        let mut app = scoring_app();
+        let (cancelled, recording) =
+            app.world
+                .resource_scope(|world, registry: Mut<HandlerRegistry>| {
+                    let mut timers = get_timers_mut(world);
+                    let params = TimerParams::default();
+                    timers.start_recording();
+                    timers.after_named(&registry, 2, "score", params.clone());
+                    let cancelled = timers.after_named(&registry, 2, "score", params);
+                    let cancelled = cancelled.expect("Failed");
+                    timers.retag(cancelled, Some(7));
+                    timers.cancel_tag(7);
+                    (cancelled, timers.stop_recording().expect("Failed"))
+                });
+        assert_eq!(recording.0.len(), 3);
+        assert_eq!(
+            recording.0[2],
+            (0, RecordedCall::Cancel { handle: cancelled })
+        );
+        update(&mut app, 3);
+        assert_eq!(score(&app), 1);
+        timers(&mut app).replay(recording);
+        update(&mut app, 4);
+        assert_eq!(score(&app), 2);
+    }
+
+    #[test]
+    fn recording_survives_the_clock_moving_backwards() {
+        let mut timers = Timers::default();
+        timers.set_elapsed_ticks(10);
+        timers.start_recording();
+        timers.set_elapsed_ticks(3);
+        timers.cancel(TimerHandle(0));
+        let recording = timers.stop_recording().expect("Failed");
+        assert_eq!(
+            recording.0,
+            [(
+                0,
+                RecordedCall::Cancel {
+                    handle: TimerHandle(0)
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn count_down_counts_each_step_then_finishes() {
+        let mut app = app();
+        let counts = log();
+        let done = log();
+        let on_tick = counts.clone();
+        timers(&mut app).count_down(
+            3,
+            2,
+            move |world: &mut World, remaining| {
+                on_tick.lock().unwrap().push((fired_on(world), remaining));
+            },
+            push_tick(&done),
+        );
+        update(&mut app, 8);
+        assert_eq!(logged(&counts), [(0, 3), (2, 2), (4, 1)]);
+        assert_eq!(logged(&done), [6]);
+    }
+
+    #[test]
+    fn cancel_where_filters_on_tag_and_target() {
+        let mut app = app();
+        let entity = app.world.spawn().id().to_bits();
+        let mut timers = timers(&mut app);
+        let kept = [
+            timers.after_tagged(60, entity, |_| {}),
+            timers.after_tagged(10, entity + 1, |_| {}),
+            timers.after(10, |_| {}),
+        ];
+        timers.after_tagged(10, entity, |_| {});
+        timers.after_tagged(50, entity, |_| {});
+        let now = timers.elapsed_ticks();
+        let cancelled =
+            timers.cancel_where(|_, target, tag| tag == Some(entity) && target <= now + 50);
+        assert_eq!(cancelled, 2);
+        let mut pending: Vec<_> = timers
+            .pending_sorted()
+            .into_iter()
+            .map(|(h, _)| h)
+            .collect();
+        pending.sort();
+        assert_eq!(pending, kept);
+    }
+
+    #[test]
+    fn counters_for_a_known_workload() {
+        let mut app = app();
+        let mut timers = timers(&mut app);
+        timers.after(10, |_| {});
+        timers.after(100, |_| {});
+        timers.every(5000, |_| {});
+        for _ in 0..=5000 {
+            run_tick(&mut app.world);
+        }
+        let timers = get_timers(&app.world);
+        assert_eq!(timers.fires(), 3);
+        // Every higher level on the first tick, as the clock starts with all digits zero,
+        // then one level 2 slot at 4096 and every level 1 slot from 64 to 4992.
+        assert_eq!(timers.cascades(), (LEVELS - 1) as u64 + 1 + 5000 / 64);
+        // The timer at 100 falls once, at 64; the one at 5000 falls at 4096 and again at
+        // 4992.
+        assert_eq!(timers.migrations(), 3);
+    }
+
+    #[test]
+    fn after_epoch_counts_from_the_mark() {
+        let mut app = app();
+        let fired = log();
+        assert!(timers(&mut app)
+            .after_epoch("round", 1, push_tick(&fired))
+            .is_none());
+        update(&mut app, 3);
+        timers(&mut app).mark_epoch("round");
+        update(&mut app, 4);
+        timers(&mut app).after_epoch("round", 6, push_tick(&fired));
+        // The tick has passed, so this fires on the next tick.
+        timers(&mut app).after_epoch("round", 2, push_tick(&fired));
+        update(&mut app, 3);
+        assert_eq!(logged(&fired), [7, 9]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "slot offset 64 is out of range for a wheel of 64 slots")]
+    fn out_of_range_slot_offsets_fail_with_a_clear_message() {
+        TimingWheel::default().schedule(MAX_INTERVAL, 0, ());
+    }
+
+    #[test]
+    fn animate_advances_the_frame_index() {
+        let mut app = app();
+        let once = app.world.spawn().insert(Health(0)).id();
+        let looping = app.world.spawn().insert(Health(0)).id();
+        let mut timers = timers(&mut app);
+        let set_index = |health: &mut Health, index| health.0 = index as u32;
+        timers.animate::<Health, _>(once, 2, 3, false, set_index);
+        timers.animate::<Health, _>(looping, 2, 3, true, set_index);
+        let mut frames = Vec::new();
+        for _ in 0..8 {
+            update(&mut app, 1);
+            let index = |entity| app.world.get::<Health>(entity).expect("Failed").0;
+            frames.push((index(once), index(looping)));
+        }
+        assert_eq!(
+            frames,
+            [
+                (0, 0),
+                (0, 0),
+                (1, 1),
+                (1, 1),
+                (2, 2),
+                (2, 2),
+                (2, 0),
+                (2, 0)
+            ]
+        );
+        app.world.entity_mut(looping).remove::<Health>();
+        update(&mut app, 2);
+        assert!(get_timers(&app.world).is_empty());
+    }
+
+    #[test]
+    fn after_least_loaded_picks_the_emptiest_tick() {
+        let mut timers = Timers::default();
+        for (after, count) in [(2, 2), (3, 1), (4, 0), (5, 0), (6, 3)] {
+            for _ in 0..count {
+                timers.after(after, |_| {});
+            }
+        }
+        assert_eq!(timers.load_at(6), 3);
+        let picked = timers.after_least_loaded(2, 6, |_| {});
+        assert_eq!(timers.scheduled_between(4, 5), [(picked, 4)]);
+        assert_eq!(timers.load_at(4), 1);
+        let picked = timers.after_least_loaded(2, 6, |_| {});
+        assert_eq!(timers.scheduled_between(5, 6), [(picked, 5)]);
+    }
+
+    #[test]
+    fn take_returns_the_closure_instead_of_running_it() {
+        let mut app = app();
+        let fired = log();
+        let once = timers(&mut app).after(1, push(&fired, "once"));
+        let ticks = fired.clone();
+        let repeating = timers(&mut app).every(1, move |_| ticks.lock().unwrap().push("repeating"));
+        let once = timers(&mut app).take(once).expect("Failed");
+        let repeating = timers(&mut app).take(repeating).expect("Failed");
+        assert!(get_timers(&app.world).is_empty());
+        update(&mut app, 3);
+        assert!(logged(&fired).is_empty());
+        repeating(&mut app.world);
+        once(&mut app.world);
+        assert_eq!(logged(&fired), ["repeating", "once"]);
+        assert!(timers(&mut app).take(TimerHandle(0)).is_none());
+    }
+
+    #[test]
+    fn drain_stages_bracket_the_timers() {
+        let fired = log();
+        let mut app = app();
+        add_marker(&mut app, TimerDrainStage::PostDrain, &fired, "post drain");
+        add_marker(&mut app, TimerDrainStage::PreDrain, &fired, "pre drain");
+        timers(&mut app).after(0, push(&fired, "timer"));
+        app.update();
+        assert_eq!(logged(&fired), ["pre drain", "timer", "post drain"]);
+    }
+
+    #[test]
+    fn set_interval_applies_from_the_next_reschedule() {
+        let mut app = app();
+        let fired = log();
+        let handle = timers(&mut app).every(4, push_ticks(&fired));
+        assert!(timers(&mut app).set_interval(handle, 2));
+        update(&mut app, 9);
+        assert_eq!(logged(&fired), [4, 6, 8]);
+        let once = timers(&mut app).after(1, |_| {});
+        assert!(!timers(&mut app).set_interval(once, 2));
+    }
+
+    #[test]
+    fn after_collect_pushes_results_in_firing_order() {
+        let mut app = app();
+        let mut timers = timers(&mut app);
+        timers.after_collect(2, |world| fired_on(world));
+        timers.after_collect(1, |world| fired_on(world));
+        update(&mut app, 1);
+        assert!(app.world.get_resource::<TimerResults<u64>>().is_none());
+        update(&mut app, 2);
+        let results = app
+            .world
+            .get_resource::<TimerResults<u64>>()
+            .expect("Failed");
+        assert_eq!(results.0, [1, 2]);
+    }
+
+    #[test]
+    fn schedule_eq_compares_what_fires_when() {
+        let build = |elapsed, tag| {
+            let mut timers = Timers::default();
+            timers.set_elapsed_ticks(elapsed);
+            timers.after_tagged(5, tag, |_| {});
+            timers.every(3, |_| {});
+            timers.after_realtime(2, |_| {});
+            timers
+        };
+        // Neither the absolute tick nor the wheel level matters.
+        assert!(build(0, 1).schedule_eq(&build(4000, 1)));
+        assert!(!build(0, 1).schedule_eq(&build(0, 2)));
+        let mut longer = build(0, 1);
+        let repeating = longer.pending_sorted()[0].0;
+        longer.set_interval(repeating, 4);
+        assert!(!longer.schedule_eq(&build(0, 1)));
+    }
+
+    #[test]
+    fn tick_sources_count_ticks() {
+        let mut world = World::new();
+        assert_eq!(PerUpdate.ticks(&world), 1);
+
+        let mut fixed = FixedTimestep::new(Duration::from_millis(10));
+        assert_eq!(fixed.ticks(&world), 0);
+        let mut time = Time::default();
+        time.update();
+        std::thread::sleep(Duration::from_millis(35));
+        time.update();
+        let delta = time.delta();
+        world.insert_resource(time);
+        let ticks = fixed.ticks(&world);
+        assert_eq!(ticks as u128, delta.as_millis() / 10);
+        assert!(fixed.accumulated < fixed.step);
+
+        let mut app = App::new();
+        app.add_plugin(TimerPlugin::with_source(EventDriven::<NextTurn>::default()))
+            .add_event::<NextTurn>();
+        let fired = log();
+        timers(&mut app).every(1, push_ticks(&fired));
+        app.update();
+        assert_eq!(get_timers(&app.world).elapsed_ticks(), 0);
+        let mut events = app
+            .world
+            .get_resource_mut::<Events<NextTurn>>()
+            .expect("Failed");
+        events.send(NextTurn);
+        events.send(NextTurn);
+        app.update();
+        assert_eq!(get_timers(&app.world).elapsed_ticks(), 2);
+        assert_eq!(logged(&fired), [1]);
+    }
+
+    #[test]
+    fn despawn_after_despawns_unless_cancelled() {
+        let mut app = app();
+        let doomed = app.world.spawn().id();
+        let spared = app.world.spawn().id();
+        let gone = app.world.spawn().id();
+        timers(&mut app).despawn_after(doomed, 2);
+        let handle = timers(&mut app).despawn_after(spared, 2);
+        timers(&mut app).despawn_after(gone, 2);
+        timers(&mut app).cancel(handle);
+        app.world.despawn(gone);
+        update(&mut app, 2);
+        assert!(app.world.get_entity(doomed).is_some());
+        update(&mut app, 1);
+        assert!(app.world.get_entity(doomed).is_none());
+        assert!(app.world.get_entity(spared).is_some());
+    }
+
+    fn reschedule_self(fired: Log<u64>) -> impl FnOnce(&mut World) + Send + Sync + 'static {
+        move |world: &mut World| {
+            fired.lock().unwrap().push(fired_on(world));
+            get_timers_mut(world).after(0, reschedule_self(fired));
+        }
+    }
+
+    #[test]
+    fn self_scheduling_timers_run_once_per_tick() {
+        let mut app = app();
+        let fired = log();
+        timers(&mut app).after(0, reschedule_self(fired.clone()));
+        update(&mut app, 3);
+        assert_eq!(logged(&fired), [0, 1, 2]);
+        assert_eq!(get_timers(&app.world).len(), 1);
+    }
+
+    #[test]
+    fn level_occupancy_counts_timers_per_level() {
+        let mut timers = Timers::default();
+        for after in [0, 63, 64, 4095, 4096, 262_144] {
+            timers.after(after, |_| {});
+        }
+        timers.after_realtime(10, |_| {});
+        assert_eq!(timers.level_occupancy(), [3, 2, 1, 1]);
+    }
+
+    /// Return an app with the `score` handler registered and a [Score] of zero.
+    fn scoring_app() -> App {
+        let mut app = app();
+        app.world
+            .get_resource_mut::<HandlerRegistry>()
+            .expect("Failed")
+            .register_or_panic("score", add_score);
+        app.world.insert_resource(Score(0));
+        app
+    }
+
+    fn score(app: &App) -> u32 {
+        app.world.get_resource::<Score>().expect("Failed").0
+    }
+
+    #[test]
+    fn duplicate_schedule_forks_named_timers() {
+        let mut app = scoring_app();
+        update(&mut app, 3);
+        app.world
+            .resource_scope(|world, registry: Mut<HandlerRegistry>| {
+                let mut timers = get_timers_mut(world);
+                timers.after_named(&registry, 2, "score", TimerParams::default());
+                timers.after_named(&registry, 5, "score", TimerParams::default());
+                timers.after(1, |_| {});
+            });
+        let (copy, dropped) = get_timers(&app.world).duplicate_schedule();
+        assert_eq!(dropped, 1);
+        let mut fork = scoring_app();
+        *get_timers_mut(&mut fork.world) = copy;
+        assert_eq!(get_timers(&fork.world).elapsed_ticks(), 3);
+
+        update(&mut fork, 3);
+        assert_eq!((score(&app), score(&fork)), (0, 1));
+        update(&mut app, 6);
+        assert_eq!((score(&app), score(&fork)), (2, 1));
+        update(&mut fork, 3);
+        assert_eq!((score(&app), score(&fork)), (2, 2));
+    }
+
+    #[test]
+    fn tick_accumulator_carries_fractional_ticks() {
+        let mut app = app();
+        timers(&mut app).set_time_scale(1.5);
+        let mut steps = Vec::new();
+        for _ in 0..4 {
+            update(&mut app, 1);
+            let timers = get_timers(&app.world);
+            steps.push((timers.elapsed_ticks(), timers.tick_accumulator()));
+        }
+        assert_eq!(steps, [(1, 0.5), (3, 0.0), (4, 0.5), (6, 0.0)]);
+    }
+
+    #[test]
+    #[cfg(feature = "audio")]
+    fn play_after_queues_the_sound() {
+        let mut app = app();
+        timers(&mut app).play_after(1, Handle::default());
+        let warnings = captured(Level::WARN, || update(&mut app, 2));
+        assert_eq!(warnings, ["cannot play a sound without an Audio resource"]);
+        app.world.insert_resource(Audio::<AudioSource>::default());
+        timers(&mut app).play_after(1, Handle::default());
+        update(&mut app, 1);
+        let audio = app.world.get_resource::<Audio>().expect("Failed");
+        assert!(audio.queue.read().is_empty());
+        update(&mut app, 1);
+        let audio = app.world.get_resource::<Audio>().expect("Failed");
+        assert_eq!(audio.queue.read().len(), 1);
+    }
+
+    #[test]
+    fn spawn_after_builds_the_bundle_when_it_fires() {
+        let mut app = app();
+        let spawned = log();
+        let then = spawned.clone();
+        timers(&mut app).spawn_after(
+            2,
+            || (Health(7),),
+            move |world, entity| then.lock().unwrap().push((fired_on(world), entity)),
+        );
+        update(&mut app, 3);
+        let spawned = logged(&spawned);
+        assert_eq!(spawned.len(), 1);
+        assert_eq!(spawned[0].0, 2);
+        assert_eq!(app.world.get::<Health>(spawned[0].1).expect("Failed").0, 7);
+    }
+
+    #[test]
+    fn extreme_delays_are_rejected_as_too_long() {
+        let mut timers = Timers::default();
+        let max = TimingWheelHierarchy::<Timer>::MAX_INTERVAL;
+        let too_long = Err(TimerError::IntervalTooLong { max });
+        assert_eq!(timers.try_after(usize::MAX, |_| {}), too_long);
+        assert_eq!(timers.try_after(max + 1, |_| {}), too_long);
+        assert_eq!(timers.try_after(u64::MAX, |_| {}), too_long);
+        assert_eq!(timers.try_after(Duration::MAX, |_| {}), too_long);
+        assert_eq!(Duration::MAX.into_ticks(60.0), usize::MAX);
+        assert!(timers.is_empty());
+        timers.set_elapsed_ticks(u64::MAX - 10);
+        assert_eq!(timers.load_at(usize::MAX), 0);
+    }
+
+    #[test]
+    fn level_tick_decomposes_the_clock_in_base_64() {
+        let mut app = app();
+        let fired = log();
+        let mut timers = timers(&mut app);
+        let elapsed = 3 * 262_144 + 5 * 4096 + 7 * 64 + 9;
+        timers.set_elapsed_ticks(elapsed);
+        let digits: Vec<_> = (0..LEVELS).map(|level| timers.level_tick(level)).collect();
+        assert_eq!(digits, [9, 7, 5, 3]);
+        timers.after(100, push_tick(&fired));
+        assert!(timers.set_level_tick(1, 8));
+        assert_eq!(timers.elapsed_ticks(), elapsed + 64);
+        assert!(!timers.set_level_tick(LEVELS, 0));
+        assert!(!timers.set_level_tick(0, 64));
+        update(&mut app, 37);
+        assert_eq!(logged(&fired), [elapsed + 100]);
+    }
+
+    #[test]
+    fn after_real_secs_converts_by_the_actual_tick_rate() {
+        let mut timers = Timers::default();
+        let mut time = Time::default();
+        timers.set_tick_rate(60.0);
+        // Without a frame to judge by, the tick rate is used.
+        let handle = timers.after_real_secs(&time, 0.5, |_| {});
+        assert_eq!(timers.remaining(handle), Some(30));
+        time.update();
+        std::thread::sleep(Duration::from_millis(20));
+        time.update();
+        let handle = timers.after_real_secs(&time, 1.0, |_| {});
+        let expected = time.delta_seconds_f64().recip().round() as usize;
+        assert_eq!(timers.remaining(handle), Some(expected));
+        timers.set_catch_up(Some(100.0));
+        timers.set_time_scale(2.0);
+        let handle = timers.after_real_secs(&time, 0.5, |_| {});
+        assert_eq!(timers.remaining(handle), Some(100));
+    }
+
+    #[test]
+    fn registering_a_name_twice_keeps_the_first_handler() {
+        fn other(_: &mut World, _: &TimerParams) {}
+        let mut registry = HandlerRegistry::default();
+        assert_eq!(registry.register("score", add_score), Ok(()));
+        assert_eq!(
+            registry.register("score", other),
+            Err(DuplicateHandler {
+                name: "score".to_string()
+            })
+        );
+        let mut world = World::new();
+        world.insert_resource(Score(0));
+        registry.get("score").expect("Failed")(&mut world, &TimerParams::default());
+        assert_eq!(world.get_resource::<Score>().expect("Failed").0, 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn register_or_panic_panics_on_a_duplicate() {
+        let mut registry = HandlerRegistry::default();
+        registry.register_or_panic("score", add_score);
+        registry.register_or_panic("score", add_score);
+    }
+
+    #[test]
+    fn paused_groups_keep_their_remaining_ticks() {
+        let mut app = app();
+        let fired = log();
+        let mut timers = timers(&mut app);
+        timers.after_tagged(3, 1, push(&fired, "paused"));
+        let cancelled = timers.after_tagged(3, 1, push(&fired, "cancelled"));
+        timers.after_tagged(3, 2, push(&fired, "other group"));
+        assert_eq!(timers.pause_group(1), 2);
+        assert_eq!(timers.pause_group(5), 0);
+        assert_eq!(timers.len(), 3);
+        update(&mut app, 10);
+        assert_eq!(logged(&fired), ["other group"]);
+        let mut timers = get_timers_mut(&mut app.world);
+        assert!(timers.cancel(cancelled));
+        assert_eq!(timers.resume_group(1), 1);
+        assert_eq!(timers.pending_sorted()[0].1, 13);
+        update(&mut app, 4);
+        assert_eq!(logged(&fired), ["other group", "paused"]);
+    }
+
+    #[test]
+    fn pending_sorted_orders_by_tick_then_handle() {
+        let mut timers = Timers::default();
+        let late = timers.after(5000, |_| {});
+        let b = timers.after(2, |_| {});
+        let a = timers.after(1, |_| {});
+        let tied = timers.after(2, |_| {});
+        assert_eq!(
+            timers.pending_sorted(),
+            [(a, 1), (b, 2), (tied, 2), (late, 5000)]
+        );
+    }
+
+    #[test]
+    fn toggle_starts_and_cancels_alternately() {
+        let mut app = app();
+        let fired = log();
+        let started = timers(&mut app).toggle(1, 3, push(&fired, "first"));
+        assert!(started.is_some());
+        assert_eq!(timers(&mut app).toggle(1, 3, push(&fired, "never")), None);
+        assert!(get_timers(&app.world).is_empty());
+        timers(&mut app).toggle(1, 3, push(&fired, "second"));
+        update(&mut app, 4);
+        assert_eq!(logged(&fired), ["second"]);
+        // Once the timer has fired, the next toggle starts a new one.
+        assert!(timers(&mut app).toggle(1, 3, |_| {}).is_some());
+    }
+
+    #[test]
+    fn max_pending_rejects_timers_until_one_finishes() {
+        let mut app = app();
+        let mut timers = timers(&mut app);
+        timers.set_max_pending(Some(2));
+        let first = timers.try_after(1, |_| {}).expect("Failed");
+        timers.try_after(5, |_| {}).expect("Failed");
+        assert_eq!(
+            timers.try_after(1, |_| {}).err(),
+            Some(TimerError::Full { max: 2 })
+        );
+        timers.cancel(first);
+        assert!(timers.try_after(1, |_| {}).is_ok());
+        update(&mut app, 2);
+        assert!(get_timers_mut(&mut app.world).try_after(1, |_| {}).is_ok());
+    }
+
+    #[test]
+    fn max_pending_drops_infallibly_scheduled_timers() {
+        let mut app = app();
+        let fired = log();
+        let mut timers = timers(&mut app);
+        timers.set_max_pending(Some(1));
+        timers.after(1, |_| {});
+        let warnings = captured(Level::WARN, || {
+            let mut timers = get_timers_mut(&mut app.world);
+            let dropped = timers.after(1, push(&fired, "dropped"));
+            assert!(!timers.is_pending(dropped));
+            timers.after_keyed(7, 1, |_| {});
+        });
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with("dropped timer"));
+        assert_eq!(get_timers(&app.world).pending_count(), 1);
+        update(&mut app, 2);
+        assert!(logged(&fired).is_empty());
+        // The dropped keyed timer does not hold on to its key.
+        let kept = get_timers_mut(&mut app.world).after_keyed(7, 1, push(&fired, "kept"));
+        assert!(get_timers(&app.world).is_pending(kept));
+        update(&mut app, 2);
+        assert_eq!(logged(&fired), ["kept"]);
+    }
+
+    #[test]
+    fn full_timers_drop_the_rest_of_a_sequence() {
+        let mut app = app();
+        let ticks = log();
+        let on_tick = ticks.clone();
+        let mut timers = timers(&mut app);
+        timers.set_max_pending(Some(1));
+        timers.count_down(
+            3,
+            2,
+            move |world: &mut World, remaining| {
+                on_tick.lock().unwrap().push(remaining);
+                // Take the slot freed by this step.
+                get_timers_mut(world).after(10, |_| {});
+            },
+            |_| {},
+        );
+        let warnings = captured(Level::WARN, || update(&mut app, 5));
+        assert_eq!(logged(&ticks), [3]);
+        assert_eq!(
+            warnings,
+            ["dropped the rest of a count down: too many pending timers (max is 1)"]
+        );
+    }
+
+    #[test]
+    fn advance_one_returns_the_due_timers_without_running_them() {
+        let mut app = app();
+        let fired = log();
+        let mut timers = timers(&mut app);
+        timers.after(0, push(&fired, "a"));
+        timers.after(0, push(&fired, "b"));
+        timers.every(1, push_ticks(&log()));
+        timers.after(1, push(&fired, "later"));
+        let due = timers.advance_one();
+        assert_eq!(due.len(), 2);
+        assert_eq!(timers.elapsed_ticks(), 1);
+        assert!(logged(&fired).is_empty());
+        for timer in due {
+            timer(&mut app.world);
+        }
+        assert_eq!(logged(&fired), ["a", "b"]);
+        let due = get_timers_mut(&mut app.world).advance_one();
+        assert_eq!(due.len(), 2);
+        for timer in due {
+            timer(&mut app.world);
+        }
+        // The repeating timer rescheduled itself when it ran.
+        assert_eq!(get_timers(&app.world).pending_sorted()[0].1, 2);
+    }
+
+    #[test]
+    fn labels_are_recorded_in_the_fire_log() {
+        let mut app = app();
+        timers(&mut app).set_fire_log(Some(4));
+        let labeled = timers(&mut app).after_labeled(1, "player_regen", |_| {});
+        timers(&mut app).after(1, |_| {});
+        update(&mut app, 2);
+        let timers = get_timers(&app.world);
+        let labels: Vec<_> = timers
+            .fire_log()
+            .iter()
+            .map(|record| (record.handle, record.label))
+            .collect();
+        assert_eq!(labels[0], (labeled, Some("player_regen")));
+        assert_eq!(labels[1].1, None);
+    }
+
+    #[test]
+    fn remaining_secs_converts_with_the_tick_rate() {
+        let mut timers = Timers::default();
+        timers.set_tick_rate(20.0);
+        let handle = timers.after(Duration::from_millis(1500), |_| {});
+        assert_eq!(timers.remaining(handle), Some(30));
+        assert_eq!(timers.remaining_secs(handle), Some(1.5));
+        timers.cancel(handle);
+        assert_eq!(timers.remaining_secs(handle), None);
+    }
+
+    #[test]
+    fn exclusive_system_in_runs_timers_at_the_end_of_the_stage() {
+        let fired = log();
+        let mut app = App::new();
+        app.add_plugin(TimerPlugin::exclusive_system_in(CoreStage::Last));
+        add_marker(&mut app, CoreStage::Last, &fired, "last");
+        add_marker(&mut app, CoreStage::PostUpdate, &fired, "post update");
+        timers(&mut app).after(0, push(&fired, "timer"));
+        app.update();
+        assert_eq!(logged(&fired), ["post update", "last", "timer"]);
+    }
+
+    #[test]
+    fn physics_stepped_ticks_once_per_step() {
+        let mut app = App::new();
+        app.add_plugin(TimerPlugin::physics_stepped());
+        let mut time = Time::default();
+        time.update();
+        std::thread::sleep(Duration::from_millis(50));
+        time.update();
+        let step = Duration::from_secs_f64(1.0 / DEFAULT_TICK_RATE);
+        let steps = (time.delta().as_nanos() / step.as_nanos()) as u64;
+        app.world.insert_resource(time);
+        let fired = log();
+        timers(&mut app).after_physics_steps(2, push_tick(&fired));
+        app.update();
+        assert!(steps >= 3);
+        assert_eq!(get_timers(&app.world).elapsed_ticks(), steps);
+        assert_eq!(logged(&fired), [2]);
+    }
+
+    #[test]
+    fn save_and_restore_named_timers() {
+        let mut app = scoring_app();
+        update(&mut app, 5);
+        app.world
+            .resource_scope(|world, registry: Mut<HandlerRegistry>| {
+                let mut timers = get_timers_mut(world);
+                timers.mark_epoch("start");
+                timers.after_named(&registry, 3, "score", TimerParams::default());
+                timers.after(1, |_| {});
+            });
+        let save = get_timers(&app.world).save();
+        assert_eq!(save.elapsed, 5);
+        assert_eq!(save.timers.len(), 1);
+        assert_eq!(save.timers[0].target, 8);
+
+        let mut restored = scoring_app();
+        let timers = Timers::from_save(
+            save.clone(),
+            restored
+                .world
+                .get_resource::<HandlerRegistry>()
+                .expect("Failed"),
+        );
+        assert_eq!(timers.save(), save);
+        *get_timers_mut(&mut restored.world) = timers;
+        update(&mut restored, 3);
+        assert_eq!(score(&restored), 0);
+        update(&mut restored, 1);
+        assert_eq!(score(&restored), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn saves_are_serializable() {
+        fn serializable<T: serde::Serialize + serde::de::DeserializeOwned>() {}
+        serializable::<TimersSave>();
+    }
+
+    #[test]
+    fn spawn_wave_staggers_timers_and_can_be_cancelled() {
+        let mut app = app();
+        let fired = log();
+        let handles = timers(&mut app).spawn_wave(9, 8, (0..4).map(|_| push_tick(&fired)));
+        assert_eq!(handles.len(), 4);
+        let targets: Vec<_> = get_timers(&app.world)
+            .pending_sorted()
+            .into_iter()
+            .map(|(_, target)| target)
+            .collect();
+        assert_eq!(targets, [0, 2, 4, 6]);
+        update(&mut app, 4);
+        assert_eq!(timers(&mut app).cancel_tag(9), 2);
+        update(&mut app, 10);
+        assert_eq!(logged(&fired), [0, 2]);
+    }
+
+    #[test]
+    fn on_migrate_reports_each_level_a_timer_falls() {
+        let mut app = app();
+        let migrations = log();
+        let reported = migrations.clone();
+        let mut timers = timers(&mut app);
+        timers.on_migrate(move |handle, from, to, remaining| {
+            reported.lock().unwrap().push((handle, from, to, remaining));
+        });
+        let handle = timers.after(5000, |_| {});
+        for _ in 0..=5000 {
+            run_tick(&mut app.world);
+        }
+        assert_eq!(
+            logged(&migrations),
+            [(handle, 2, 1, 904), (handle, 1, 0, 8)]
+        );
+    }
+
+    #[test]
+    fn tween_progress_rises_to_one() {
+        let mut app = app();
+        let progress = log();
+        let values = progress.clone();
+        timers(&mut app).tween(4, move |world: &mut World, value| {
+            values.lock().unwrap().push((fired_on(world), value));
+        });
+        update(&mut app, 6);
+        assert_eq!(
+            logged(&progress),
+            [(0, 0.25), (1, 0.5), (2, 0.75), (3, 1.0)]
+        );
+        let instant = log();
+        let values = instant.clone();
+        timers(&mut app).tween(0, move |_: &mut World, value| {
+            values.lock().unwrap().push(value)
+        });
+        update(&mut app, 2);
+        assert_eq!(logged(&instant), [1.0]);
+    }
+
+    #[test]
+    fn advance_one_starts_each_timer_when_its_closure_runs() {
+        let mut app = app();
+        let fired = log();
+        let mut timers = timers(&mut app);
+        timers.set_fire_log(Some(4));
+        let later = Arc::new(Mutex::new(None));
+        let cancel = later.clone();
+        timers.after(0, move |world| {
+            let later = cancel.lock().unwrap().unwrap();
+            assert!(get_timers_mut(world).cancel(later));
+        });
+        *later.lock().unwrap() = Some(timers.after(0, push(&fired, "cancelled")));
+        let filtered = timers.after_keyed(7, 0, push(&fired, "filtered"));
+        let mut due = timers.advance_one();
+        assert_eq!(due.len(), 3);
+        // Filter out the keyed timer.
+        due.pop();
+        for timer in due {
+            timer(&mut app.world);
+        }
+        assert!(logged(&fired).is_empty());
+        let timers = get_timers(&app.world);
+        assert_eq!((timers.fires(), timers.total_fired()), (1, 1));
+        assert_eq!(timers.fire_log().len(), 1);
+        // The filtered timer is cancelled once the next tick starts, freeing its key.
+        update(&mut app, 1);
+        let timers = get_timers(&app.world);
+        assert_eq!((timers.total_fired(), timers.total_cancelled()), (1, 2));
+        assert!(timers.is_empty());
+        let mut timers = get_timers_mut(&mut app.world);
+        let replacement = timers.after_keyed(7, 1, |_| {});
+        assert_ne!(replacement, filtered);
+        assert!(timers.is_pending(replacement));
+    }
+
+    #[test]
+    fn cancelled_nonsend_timers_drop_their_closures_on_the_next_tick() {
+        let mut app = app();
+        let fired = Rc::new(Cell::new(false));
+        let seen = fired.clone();
+        let handle = Timers::after_nonsend(&mut app.world, 5, move |_| seen.set(true));
+        assert_eq!(Rc::strong_count(&fired), 2);
+        assert!(timers(&mut app).cancel(handle));
+        update(&mut app, 1);
+        assert_eq!(Rc::strong_count(&fired), 1);
+        let non_send = app.world.get_non_send_resource::<NonSendTimers>().unwrap();
+        assert!(non_send.timers.is_empty());
+    }
+
+    #[test]
+    fn nonsend_timers_are_not_kept_when_dropped_for_being_full() {
+        let mut app = app();
+        timers(&mut app).set_max_pending(Some(0));
+        let handle = Timers::after_nonsend(&mut app.world, 1, |_| {});
+        assert!(!get_timers(&app.world).is_pending(handle));
+        let non_send = app.world.get_non_send_resource::<NonSendTimers>().unwrap();
+        assert!(non_send.timers.is_empty());
+    }
+
+    #[test]
+    fn catching_up_runs_the_ticks_owed_up_to_the_limit() {
+        let mut app = app();
+        let mut time = Time::default();
+        time.update();
+        std::thread::sleep(Duration::from_millis(20));
+        time.update();
+        let owed = time.delta_seconds_f64() * 1000.0;
+        app.world.insert_resource(time);
+        get_timers_mut(&mut app.world).set_catch_up(Some(1000.0));
+        assert_eq!(update_ticks(&mut app.world), owed as usize);
+        let behind = get_timers(&app.world).behind;
+        assert!((behind - owed.fract()).abs() < 1e-9);
+        // With a limit, the ticks owed beyond it are dropped.
+        get_timers_mut(&mut app.world).set_max_catch_up(Some(5));
+        assert_eq!(update_ticks(&mut app.world), 5);
+        assert_eq!(get_timers(&app.world).behind, 0.0);
+    }
+
+    #[test]
+    fn throttle_forgets_keys_once_their_interval_has_passed() {
+        let mut app = app();
+        for key in 0..10 {
+            timers(&mut app).throttle(key, 3, |_| {});
+        }
+        update(&mut app, 1);
+        assert_eq!(get_timers(&app.world).throttled.len(), 10);
+        // A key whose interval has yet to pass is still held back.
+        let held = timers(&mut app).throttle(0, 3, |_| {});
+        assert_eq!(get_timers(&app.world).remaining(held), Some(2));
+        update(&mut app, 3);
+        timers(&mut app).throttle(20, 3, |_| {});
+        let timers = get_timers(&app.world);
+        assert_eq!(timers.throttled.keys().collect::<Vec<_>>(), [&0]);
+        assert_eq!(timers.throttle_expiry.len(), 1);
     }
 }